@@ -10,6 +10,9 @@ pub enum Node<'a> {
     //Contiguous whitespace
     Whitespace(&'a str),
 
+    // |= Raw text =|, kept exactly as written
+    Raw(&'a str),
+
     // <tag>
     Tag {
         name: &'a str,
@@ -38,6 +41,7 @@ impl<'a> Node<'a> {
         let node_label = match self {
             Node::Text(text) => format!("{:?}", text),
             Node::Whitespace(ws) => format!("Whitespace [{}]", ws),
+            Node::Raw(text) => format!("Raw {:?}", text),
             Node::Tag { name } => format!("Tag <{}>", name),
             Node::StyleDefinition(style_id) => {
                 registry.display_style_definition(*style_id)
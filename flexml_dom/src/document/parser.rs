@@ -17,6 +17,8 @@ pub struct FlexmlDocument<'a> {
     pub(crate) nodes: Vec<Node<'a>>,
     pub(crate) styles: Vec<Node<'a>>,
     pub(crate) name: String,
+    /// Title shown by `<title>` in page regions, empty unless set
+    pub(crate) title: String,
 
     base_path: Option<PathBuf>,
     parsed: bool,
@@ -41,6 +43,7 @@ impl<'a> FlexmlDocument<'a> {
             nodes: Vec::new(),
             styles: Vec::new(),
             name: "FlexmlDocument".to_string(),
+            title: String::new(),
             lexer: Token::lexer(input),
             peeked: None,
             header_parsed: false,
@@ -82,6 +85,11 @@ impl<'a> FlexmlDocument<'a> {
         self
     }
 
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = title.to_owned();
+        self
+    }
+
     pub fn parse(mut self) -> Self {
         if self.parsed { return self }
 
@@ -106,7 +114,7 @@ impl<'a> FlexmlDocument<'a> {
                 Node::StyleDefinition(_) => {
                     self.styles.push(node);
                 },
-                Node::BoxContainer {..} => {
+                Node::BoxContainer {..} | Node::Tag{..} => {
                     if !text_group.is_empty() {
                         self.nodes.push(Node::BoxContainer {
                             styles: vec![],
@@ -115,15 +123,12 @@ impl<'a> FlexmlDocument<'a> {
                     }
                     self.nodes.push(node)
                 },
-                Node::Text(_) => {
+                Node::Text(_) | Node::Raw(_) => {
                     text_group.push(node);
                 },
                 Node::Whitespace(_) => {
                     text_group.push(node);
                 },
-                Node::Tag{..} => {
-                    todo!()
-                }
             }
         }
 
@@ -344,7 +349,18 @@ impl<'a> FlexmlDocument<'a> {
             self.warn(start..end, UnclosedRawContainer);
         }
 
-        Node::Text(&self.input[start..end])
+        Node::Raw(&self.input[start..end])
+    }
+
+    /// Page selector definitions like `{flexml:first = ...}` are named after the root
+    /// style and the selector. Returns that name when a selector follows the root name
+    fn take_page_selector(&mut self, name: &str) -> Option<String> {
+        let &(StyleValue, value) = self.peek()? else { return None };
+        let selector = value.trim_start().strip_prefix(':')?.trim_start();
+        let name = StyleRegistry::page_selector_name(name, selector)?;
+
+        self.take();
+        Some(name)
     }
 
     /// Style containers have named and then whitespace or separator
@@ -364,7 +380,7 @@ impl<'a> FlexmlDocument<'a> {
         let name = match self.peek() {
             Some((StyleName, _)) => {
                 let (_, name) = self.take().unwrap(); // consume the name
-                name
+                self.take_page_selector(name).unwrap_or_else(|| name.to_string())
             }
             _ => {
                 // No Named, fallback to text
@@ -400,7 +416,7 @@ impl<'a> FlexmlDocument<'a> {
             }
         }
 
-        let registered = self.style_registry.register_style(name, styles, forwarders);
+        let registered = self.style_registry.register_style(&name, styles, forwarders);
 
         if registered.atomic {
             // Tried to register a style for an atomic style definition
@@ -502,12 +518,16 @@ impl<'a> FlexmlDocument<'a> {
                     let (_, name) = self.take().unwrap();
                     let mut value = None;
 
-                    if let Some((StyleValue, arg_val)) = self.peek() {
-                        // Style values come in with separators and possibly quoted
-                        value = Some(arg_val
-                            .trim_start_matches([':', ' ', '\t', '"'])
-                            .trim_end_matches('"')
-                        );
+                    if let Some(&(StyleValue, arg_val)) = self.peek() {
+                        let arg_val = if self.style_registry.takes_list_value(name) { self.take_list_value() } else { arg_val };
+
+                        // Style values come in with separators and possibly quoted.
+                        // Lists of quoted values keep their quotes for the value parser
+                        let arg_val = arg_val.trim_start_matches([':', ' ', '\t']);
+                        value = Some(match arg_val.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                            Some(quoted) if !quoted.contains('"') => quoted,
+                            _ => arg_val,
+                        });
                         self.take();
                     }
 
@@ -656,6 +676,50 @@ impl<'a> FlexmlDocument<'a> {
         found
     }
 
+    /// Extend the peeked style value over the items of its list. Items are quoted
+    /// strings, `+tag`/`-tag` toggles and tags with a number, `"tnum" "ss01" -liga`
+    /// or `wght 650 wdth 90`. Text after the last item stays content
+    fn take_list_value(&mut self) -> &'a str {
+        let value = self.lexer.slice();
+        let rest = self.lexer.remainder();
+        let is_tag = |word: &str| word.len() == 4 && word.starts_with(|c: char| c.is_ascii_alphabetic()) && word.chars().all(|c| c.is_ascii_alphanumeric());
+        let is_number = |word: &str| word.parse::<f32>().is_ok();
+
+        // A value ending on an axis tag goes on with its number
+        let mut wants_number = value.rsplit([':', ' ', '\t']).next().is_some_and(is_tag);
+        let mut len = 0;
+
+        loop {
+            let after = &rest[len..];
+            let item = after.trim_start_matches([' ', '\t']);
+            if item.len() == after.len() { break }
+
+            let word_len = |item: &str| item.find([' ', '\t', '\r', '\n', ']', '}']).unwrap_or(item.len());
+            let word = &item[..word_len(item)];
+            let next = item[word.len()..].trim_start_matches([' ', '\t']);
+            let next_word = &next[..word_len(next)];
+
+            let item_len = if wants_number {
+                is_number(word).then_some(word.len())
+            } else if let Some(quoted) = item.strip_prefix('"') {
+                quoted.find('"').map(|end| end + 2)
+            } else if word.starts_with(['+', '-']) && is_tag(&word[1..]) {
+                Some(word.len())
+            } else if is_tag(word) && next.len() < item.len() - word.len() && is_number(next_word) {
+                Some(item.len() - next.len() + next_word.len())
+            } else {
+                None
+            };
+
+            let Some(item_len) = item_len else { break };
+            len += after.len() - item.len() + item_len;
+            wants_number = false;
+        }
+
+        self.lexer.bump(len);
+        self.lexer.slice()
+    }
+
     /// Skip all whitespace and newlines
     fn skip_whitespace(&mut self) {
         while let Some((tok, _)) = self.peek() {
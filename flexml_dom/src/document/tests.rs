@@ -1,6 +1,8 @@
 use std::ops::RangeInclusive;
 use super::nodes::Node;
 use super::parser::{FlexmlDocument};
+use crate::styles::context::{FontFeatures, FontVariations};
+use crate::styles::style::StyleValue;
 
 pub(super)  fn check_inputs<'a>(
     inputs: &[&'a str],
@@ -148,7 +150,7 @@ fn parse_raw_block() {
         1..=1,
         0..=0,
         &[
-            |n| matches!(n, Node::Text(text) if text.contains("This is raw")),
+            |n| matches!(n, Node::Raw(text) if text.contains("This is raw")),
         ],
     );
 }
@@ -167,7 +169,7 @@ fn parse_unclosed_raw_warns() {
         1..=1, // Each input should produce exactly 1 node
         1..=1, // Each input should produce exactly 1 warning
         &[
-            |n| matches!(n, Node::Text(_)), // Raw fallback always returns Text node
+            |n| matches!(n, Node::Raw(_)), // Unclosed raw still returns a Raw node
         ],
     );
 }
@@ -288,3 +290,34 @@ fn newline_handling() {
 
     assert_eq!(document.nodes.len(), 0);
 }
+
+#[test]
+fn parse_font_settings_lists() {
+    let inputs = &[
+        "[fontFeatures: \"tnum\" \"ss01\" -liga + fontVariations: wght 650 wdth 90 Total]",
+        "{flexml = fontFeatures: \"tnum\" \"ss01\" -liga + fontVariations: wght 650 wdth 90} [Total]",
+    ];
+
+    check_inputs(
+        inputs,
+        1..=2,
+        0..=0,
+        &[],
+    );
+
+    let document = FlexmlDocument::new(inputs[0]).parse();
+    let Node::BoxContainer { styles, children } = &document.nodes[0] else { panic!("Expected a box") };
+
+    let mut features = FontFeatures::default();
+    features.set(*b"tnum", 1);
+    features.set(*b"ss01", 1);
+    features.set(*b"liga", 0);
+    let mut variations = FontVariations::default();
+    variations.set(*b"wght", 650.0);
+    variations.set(*b"wdth", 90.0);
+
+    // Both lists are taken whole as values, the text after them stays text
+    assert!(styles.iter().any(|style| style.value == StyleValue::FontFeatures(features)));
+    assert!(styles.iter().any(|style| style.value == StyleValue::FontVariations(variations)));
+    assert!(matches!(children.as_slice(), [Node::Text("Total")]));
+}
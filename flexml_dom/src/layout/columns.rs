@@ -0,0 +1,288 @@
+use crate::layout::tree::{LayoutNodeKind, LayoutTree};
+use crate::styles::context::{Dimension, StyleContext};
+use taffy::{compute_block_layout, AvailableSpace, LayoutInput, LayoutOutput, LayoutPartialTree, Line, NodeId, Point, RequestedAxis, RunMode, Size, SizingMode};
+
+/// Blocks with a column count or width flow their content through columns
+pub(super) fn is_multi_column(style: &StyleContext) -> bool {
+    style.column_count() > 1 || style.column_width() != Dimension::Auto
+}
+
+/// Number of columns and their width within the available width.
+/// A column width makes as many columns as fit, capped by the column count
+fn column_count_and_width(style: &StyleContext, width: f32, gap: f32) -> (usize, f32) {
+    let em = style.resolved_font_size();
+    let rem = style.resolved_root_font_size();
+    let dpi = style.dpi();
+
+    let ideal_width = match style.column_width() {
+        Dimension::Auto => None,
+        d => Some(d.as_pixels(width, rem, em, dpi)).filter(|w| *w > 0.0),
+    };
+
+    let count = match (style.column_count() as usize, ideal_width) {
+        (0, None) => 1,
+        (count, None) => count,
+        (count, Some(ideal_width)) => {
+            let fits = ((width + gap) / (ideal_width + gap)).floor().max(1.0) as usize;
+            if count == 0 { fits } else { fits.min(count) }
+        }
+    };
+
+    let column_width = ((width - (count - 1) as f32 * gap) / count as f32).max(0.0);
+    (count, column_width)
+}
+
+/// A piece of the content flow that moves to a column as a whole.
+/// Inline content is sliced by line, blocks stay together
+struct Slice {
+    child: usize,
+    lines: Option<std::ops::Range<usize>>,
+    top: f32,
+    bottom: f32,
+}
+
+/// Split the children of a laid out flow into slices, from the top of the flow down
+fn collect_slices(tree: &LayoutTree, children: &[NodeId]) -> Vec<Slice> {
+    let mut slices = vec![];
+
+    for (child, &child_id) in children.iter().enumerate() {
+        let node = tree.node_from_id(child_id);
+        let location = node.unrounded_layout.location;
+        let size = node.unrounded_layout.size;
+
+        let inline_layout = node.inline_layout.as_ref()
+            .filter(|_| matches!(node.kind, LayoutNodeKind::InlineContent));
+
+        let Some(inline_layout) = inline_layout else {
+            slices.push(Slice { child, lines: None, top: location.y, bottom: location.y + size.height });
+            continue;
+        };
+
+        let visible_lines = node.inline_truncation.map(|t| t.lines).unwrap_or(usize::MAX);
+        let line_count = inline_layout.len().min(visible_lines);
+
+        // Lines beside an initial letter stay with it
+        let first_slice = node.inline_initial_letter.as_ref().map_or(1, |letter| letter.lines).clamp(1, line_count.max(1));
+
+        let mut start = 0;
+        while start < line_count {
+            let end = if start == 0 { first_slice } else { start + 1 };
+            let top = inline_layout.get(start).map_or(0.0, |line| line.metrics().min_coord);
+            let mut bottom = inline_layout.get(end - 1).map_or(0.0, |line| line.metrics().max_coord);
+            if start == 0 && let Some(letter) = &node.inline_initial_letter {
+                bottom = bottom.max(letter.bottom(inline_layout));
+            }
+
+            slices.push(Slice { child, lines: Some(start..end), top: location.y + top, bottom: location.y + bottom });
+            start = end;
+        }
+    }
+
+    slices
+}
+
+/// Fill columns of the given height in order. The last column takes what is left.
+/// Returns the column and the flow position it starts at for each slice
+fn fill_columns(slices: &[Slice], count: usize, height: f32) -> Vec<(usize, f32)> {
+    let mut placed = Vec::with_capacity(slices.len());
+    let mut column = 0;
+    let mut start = slices.first().map_or(0.0, |slice| slice.top);
+    let mut column_empty = true;
+
+    for slice in slices {
+        if !column_empty && slice.bottom - start > height + 0.01 && column + 1 < count {
+            column += 1;
+            start = slice.top;
+        }
+
+        placed.push((column, start));
+        column_empty = false;
+    }
+
+    placed
+}
+
+/// Height of the tallest column
+fn filled_height(slices: &[Slice], placed: &[(usize, f32)]) -> f32 {
+    slices.iter().zip(placed)
+        .map(|(slice, (_, start))| slice.bottom - start)
+        .fold(0.0, f32::max)
+}
+
+/// Shortest column height that fits the content in the given columns
+fn balanced_height(slices: &[Slice], count: usize) -> f32 {
+    let tallest = slices.iter().map(|slice| slice.bottom - slice.top).fold(0.0, f32::max);
+    let total = match (slices.first(), slices.last()) {
+        (Some(first), Some(last)) => last.bottom - first.top,
+        _ => return 0.0,
+    };
+
+    let fits = |height: f32| filled_height(slices, &fill_columns(slices, count, height)) <= height + 0.01;
+
+    let (mut low, mut high) = (tallest, total.max(tallest));
+    if fits(low) { return low }
+
+    for _ in 0..24 {
+        let mid = (low + high) / 2.0;
+        if fits(mid) { high = mid } else { low = mid }
+        if high - low < 0.5 { break }
+    }
+
+    high
+}
+
+/// Columns on one page, filled from a run of slices
+struct ColumnSet {
+    slices: std::ops::Range<usize>,
+    /// Column and flow position each slice of the set starts at
+    placed: Vec<(usize, f32)>,
+    /// Top of the set below the first one
+    top: f32,
+    height: f32,
+}
+
+/// Fill the columns of each page in turn to the room on that page, the content
+/// left for the last page is balanced. A first page with no room for the first
+/// slice is left empty. Without rooms all content is balanced in one set
+fn fill_column_sets(slices: &[Slice], count: usize, rooms: &[f32]) -> Vec<ColumnSet> {
+    let room = |set: usize| rooms.get(set).or(rooms.last()).copied().unwrap_or(f32::INFINITY);
+    let mut sets: Vec<ColumnSet> = vec![];
+    let mut first = 0;
+    let mut top = 0.0;
+
+    while first < slices.len() {
+        let rest = &slices[first..];
+        let page_room = room(sets.len());
+        let balanced = balanced_height(rest, count);
+
+        let (taken, placed, height) = if balanced <= page_room + 0.01 {
+            let placed = fill_columns(rest, count, balanced);
+            let height = filled_height(rest, &placed);
+            (rest.len(), placed, height)
+        } else if sets.is_empty() && rest[0].bottom - rest[0].top > page_room + 0.01 && page_room < room(1) {
+            (0, vec![], page_room)
+        } else {
+            let mut placed = fill_columns(rest, count, page_room);
+            // Slices pushed below the last column move to the next page
+            let taken = rest.iter().zip(&placed)
+                .position(|(slice, &(column, start))| column + 1 == count && slice.bottom - start > page_room + 0.01)
+                .unwrap_or(rest.len())
+                .max(1);
+            placed.truncate(taken);
+            let height = filled_height(&rest[..taken], &placed);
+            (taken, placed, height)
+        };
+
+        sets.push(ColumnSet { slices: first..first + taken, placed, top, height });
+        first += taken;
+        top += height;
+    }
+
+    sets
+}
+
+/// Lay out the content of a multi-column block.
+///
+/// Content is laid out as a single block at the column width, then sliced
+/// by line and moved into columns filled to the room on each page, the last
+/// page of them balanced. Lines of inline content moved away from the first
+/// line of their node keep their offset in `inline_line_columns`, blocks are
+/// moved as a whole
+pub(super) fn compute_columns_layout(tree: &mut LayoutTree, node_id: NodeId, inputs: LayoutInput) -> LayoutOutput {
+    let style = tree.node_from_id(node_id).style_context;
+    let children = tree.node_from_id(node_id).children.clone();
+    let rooms = tree.node_from_id(node_id).column_pages.clone();
+
+    for &child_id in &children {
+        tree.node_from_id_mut(child_id).inline_line_columns.clear();
+    }
+    tree.node_from_id_mut(node_id).column_rules.clear();
+    tree.node_from_id_mut(node_id).column_breaks.clear();
+
+    // Content sizes are measured as a single column
+    let Some(width) = inputs.known_dimensions.width.or(inputs.available_space.width.into_option()) else {
+        return compute_block_layout(tree, node_id, inputs);
+    };
+
+    let em = style.resolved_font_size();
+    let rem = style.resolved_root_font_size();
+    let dpi = style.dpi();
+
+    // Columns are an em apart unless the gap is set
+    let gap = if style.has_column_gap() { style.column_gap().as_pixels(width, rem, em, dpi) } else { em };
+    let (count, column_width) = column_count_and_width(&style, width, gap);
+
+    let flow = compute_block_layout(tree, node_id, LayoutInput {
+        run_mode: RunMode::PerformLayout,
+        sizing_mode: SizingMode::InherentSize,
+        axis: RequestedAxis::Both,
+        known_dimensions: Size { width: Some(column_width), height: None },
+        parent_size: Size { width: Some(column_width), height: inputs.parent_size.height },
+        available_space: Size { width: AvailableSpace::Definite(column_width), height: AvailableSpace::MaxContent },
+        vertical_margins_are_collapsible: Line::FALSE,
+    });
+
+    let slices = collect_slices(tree, &children);
+    if count == 1 || slices.is_empty() {
+        let size = Size { width, height: flow.size.height };
+        return LayoutOutput::from_sizes_and_baselines(size, size, flow.first_baselines);
+    }
+
+    let sets = fill_column_sets(&slices, count, &rooms);
+    let flow_top = slices[0].top;
+
+    let mut offsets = vec![Point::ZERO; slices.len()];
+    for set in &sets {
+        for (index, &(column, start)) in set.slices.clone().zip(&set.placed) {
+            offsets[index] = Point {
+                x: column as f32 * (column_width + gap),
+                y: flow_top + set.top - start,
+            };
+        }
+    }
+
+    for (child, &child_id) in children.iter().enumerate() {
+        let moves: Vec<(&Slice, Point<f32>)> = slices.iter().zip(&offsets)
+            .filter(|(slice, _)| slice.child == child)
+            .map(|(slice, &offset)| (slice, offset))
+            .collect();
+
+        let Some(&(_, first)) = moves.first() else { continue };
+
+        let mut layout = tree.node_from_id(child_id).unrounded_layout;
+        layout.location.x += first.x;
+        layout.location.y += first.y;
+        tree.set_unrounded_layout(child_id, &layout);
+
+        // Lines are placed relative to the first line of their node
+        if moves.iter().any(|(_, moved)| *moved != first) {
+            let node = tree.node_from_id_mut(child_id);
+            for (slice, moved) in &moves {
+                let lines = slice.lines.clone().unwrap_or_default();
+                node.inline_line_columns.resize(lines.end, Point::ZERO);
+                for line in lines {
+                    node.inline_line_columns[line] = Point { x: moved.x - first.x, y: moved.y - first.y };
+                }
+            }
+        }
+    }
+
+    // Rules run down the filled columns of each page, which can end at the top of each set after the first
+    let node = tree.node_from_id_mut(node_id);
+    for (index, set) in sets.iter().enumerate() {
+        let top = if index == 0 { 0.0 } else { flow_top + set.top };
+        let bottom = flow_top + set.top + set.height;
+        let used_columns = set.placed.last().map_or(1, |(column, _)| column + 1);
+
+        node.column_rules.extend((1..used_columns)
+            .map(|column| (column as f32 * (column_width + gap) - gap / 2.0, top..bottom)));
+        if index > 0 {
+            node.column_breaks.push(top);
+        }
+    }
+
+    let height = flow_top + sets.last().map_or(0.0, |set| set.top + set.height);
+    let size = Size { width, height };
+
+    LayoutOutput::from_sizes_and_baselines(size, size, flow.first_baselines)
+}
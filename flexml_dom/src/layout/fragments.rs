@@ -1,6 +1,8 @@
-use parley::{Font, Glyph, PositionedLayoutItem, Style};
+use parley::{Font, Glyph, GlyphRun, PositionedLayoutItem, RunMetrics, Style};
 use parley::swash::{NormalizedCoord, Synthesis};
-use crate::styles::context::Color;
+use crate::styles::context::{Color, Float, Image, Leader, StyleContext, TextDecoration, TextDecorationStyle};
+
+pub use crate::styles::context::{BgPosition, BgRepeat, BgSize};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rect {
@@ -47,11 +49,26 @@ pub struct GlyphRunFragment {
     pub style: Style<[u8; 4]>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecorationStyle {
+    Solid,
+    Dashed,
+    Wavy,
+    Double,
+}
+
 #[derive(Debug)]
 pub enum FragmentKind {
     Text(GlyphRunFragment),
+    /// A decoration line for a text run. Bounds hold a single line
+    /// of the given thickness, double lines are drawn one thickness below
+    TextDecoration{color: Color, thickness: f32, style: DecorationStyle},
     ColorBackground{color: Color, radius: Radius},
     ColorBorder{radius: Radius, color: Color, weight: f32},
+    /// A background image clipped to the bounds. The image is the path id
+    /// of its file, it is sized and placed in the area of its box once its
+    /// pixel size is known. Boxes cut by a page keep their whole area
+    Image{image: usize, area: Rect, radius: Radius, size: BgSize, position: BgPosition, repeat: BgRepeat},
     Debug
 }
 
@@ -82,25 +99,58 @@ impl Fragment {
 #[derive(Debug)]
 pub struct FragmentGroup {
     pub bounds: Rect,
+    /// The whole box of the group, before it is split across pages
+    pub area: Rect,
     pub fragments: Vec<Fragment>,
     pub subgroups: Vec<FragmentGroup>,
     pub splittable: bool,
+    /// Opacity the group is painted with, as one layer
+    pub opacity: f32,
+    /// Clockwise rotation in degrees around the center of the area,
+    /// so each page of a split group turns around the same point
+    pub rotation: f32,
 }
 
 impl FragmentGroup {
     pub(crate) fn new(bounds: Rect) -> Self {
         Self {
             bounds,
+            area: bounds,
             fragments: vec![],
             subgroups: vec![],
             splittable: true,
+            opacity: 1.0,
+            rotation: 0.0,
+        }
+    }
+
+    /// Move the group and everything in it
+    pub(crate) fn translate(&mut self, x: f32, y: f32) {
+        self.bounds.x += x;
+        self.bounds.y += y;
+        self.area.x += x;
+        self.area.y += y;
+
+        for fragment in &mut self.fragments {
+            fragment.bounds.x += x;
+            fragment.bounds.y += y;
+
+            if let FragmentKind::Image { area, .. } = &mut fragment.kind {
+                area.x += x;
+                area.y += y;
+            }
+        }
+
+        for subgroup in &mut self.subgroups {
+            subgroup.translate(x, y);
         }
     }
 }
 
 
 
-use taffy::NodeId;
+use taffy::{NodeId, Point};
+use crate::layout::inline::{char_glyph, ellipsis_glyphs, is_line_room_box, is_tab_box, line_hyphen, script_baseline_shift, InitialLetter, INITIAL_LETTER_BOX_ID};
 use crate::layout::tree::{LayoutNode, LayoutNodeKind, LayoutTree};
 
 impl FragmentGroup {
@@ -142,12 +192,18 @@ impl FragmentGroup {
                              glyph_run.baseline,
                     );
                 }
+                FragmentKind::TextDecoration { color, thickness, style } => {
+                    println!("{}{}Text Decoration: {:?} {:?}, thickness: {:.1}", child_indent, frag_branch, style, color, thickness);
+                }
                 FragmentKind::ColorBackground { color, .. } => {
                     println!("{}{}Background Color: {:?}", child_indent, frag_branch, color);
                 }
                 FragmentKind::ColorBorder { color, weight, .. } => {
                     println!("{}{}Border Color: {:?}, weight: {:.1}", child_indent, frag_branch, color, weight);
                 }
+                FragmentKind::Image { image, size, .. } => {
+                    println!("{}{}Image: {}, size: {:?}", child_indent, frag_branch, image, size);
+                }
                 FragmentKind::Debug => {
                     println!("Debug");
                 }
@@ -166,48 +222,249 @@ impl FragmentGroup {
 
 
 fn container_style_fragments(container: &LayoutNode, offset_x: f32, offset_y: f32) -> Option<Vec<Fragment>> {
-    let em = container.style_context.resolved_font_size();
-    let rem = container.style_context.resolved_root_font_size();
-    let dpi = container.style_context.dpi();
+    let layout = &container.final_layout;
+
+    // Layout location and size describe the border box, margins sit outside of it
+    let bounds = Rect::new(
+        offset_x + layout.location.x,
+        offset_y + layout.location.y,
+        layout.size.width,
+        layout.size.height,
+    );
 
-    let has_bg = container.style_context.bg_color().3 != 0;
-    let has_border = container.style_context.border_color().3 != 0;
+    let fragments = style_fragments(&container.style_context, bounds, false);
+    if fragments.is_empty() { None } else { Some(fragments) }
+}
 
-    //No fragments to render
-    if !has_bg && !has_border { return None }
+/// Background color, background image and border of a box. Inset borders are
+/// drawn inside the bounds, as on pages where the edge would cut them
+pub(super) fn style_fragments(style: &StyleContext, bounds: Rect, inset_border: bool) -> Vec<Fragment> {
+    let em = style.resolved_font_size();
+    let rem = style.resolved_root_font_size();
+    let dpi = style.dpi();
 
-    let mut fragments = vec![];
-    let layout = &container.final_layout;
-    let style = &container.style_context;
+    let has_bg = style.bg_color().3 != 0;
+    let has_border = style.border_color().3 != 0;
+    let image = match style.bg_image() {
+        Image::UserDefined(image) => Some(image),
+        Image::None => None,
+    };
 
+    //No fragments to render
+    if !has_bg && !has_border && image.is_none() { return vec![] }
 
-    let bounds = Rect::new(
-        offset_x + layout.location.x + layout.margin.left,
-        offset_y + layout.location.y + layout.margin.top,
-        layout.size.width - (layout.margin.left + layout.margin.right),
-        layout.size.height - (layout.margin.top + layout.margin.bottom),
-    );
+    let mut fragments = vec![];
 
     let radius = Radius::new(
-        style.border_top_left_radius().as_pixels(layout.size.width, rem, em, dpi),
-        style.border_top_right_radius().as_pixels(layout.size.width, rem, em, dpi),
-        style.border_bottom_left_radius().as_pixels(layout.size.width, rem, em, dpi),
-        style.border_bottom_right_radius().as_pixels(layout.size.width, rem, em, dpi)
+        style.border_top_left_radius().as_pixels(bounds.width, rem, em, dpi),
+        style.border_top_right_radius().as_pixels(bounds.width, rem, em, dpi),
+        style.border_bottom_left_radius().as_pixels(bounds.width, rem, em, dpi),
+        style.border_bottom_right_radius().as_pixels(bounds.width, rem, em, dpi)
     );
 
     if has_bg {
         fragments.push(Fragment::bg(bounds, radius, style.bg_color()))
     }
 
+    if let Some(image) = image {
+        fragments.push(Fragment {
+            bounds,
+            kind: FragmentKind::Image { image, area: bounds, radius, size: style.bg_size(), position: style.bg_position(), repeat: style.bg_repeat() },
+        });
+    }
+
     if has_border {
-        let border_weight = style.border_width().as_pixels(layout.size.width, rem, em, dpi);
-        fragments.push(Fragment::border(bounds, radius, style.border_color(), border_weight))
+        let border_weight = style.border_width().as_pixels(bounds.width, rem, em, dpi);
+        let inset = if inset_border { border_weight / 2.0 } else { 0.0 };
+        let border_bounds = Rect::new(bounds.x + inset, bounds.y + inset, bounds.width - 2.0 * inset, bounds.height - 2.0 * inset);
+        fragments.push(Fragment::border(border_bounds, radius, style.border_color(), border_weight))
     }
 
-    Some(fragments)
+    fragments
 }
 
 
+/// Rules drawn down the middle of the gaps between filled columns
+fn column_rule_fragments(columns: &LayoutNode, offset_x: f32, offset_y: f32) -> Vec<Fragment> {
+    let style = &columns.style_context;
+    let layout = &columns.final_layout;
+    let width = style.column_rule_width().as_pixels(layout.size.width, style.resolved_root_font_size(), style.resolved_font_size(), style.dpi());
+    let color = if style.has_column_rule_color() { style.column_rule_color() } else { style.color() };
+
+    if width <= 0.0 || color.3 == 0 { return vec![] }
+
+    columns.column_rules.iter()
+        .map(|(x, span)| Fragment::bg(
+            Rect::new(offset_x + layout.location.x + x - width / 2.0, offset_y + layout.location.y + span.start, width, span.end - span.start),
+            Radius::zero(),
+            color,
+        ))
+        .collect()
+}
+
+/// Build the decoration line for a glyph run.
+/// Offsets and thickness come from the font run metrics unless the
+/// style sets its own thickness.
+fn text_decoration_fragment(style: &StyleContext, metrics: &RunMetrics, x: f32, baseline_y: f32, width: f32) -> Option<Fragment> {
+    let (offset, font_thickness) = match style.text_decoration() {
+        TextDecoration::None => return None,
+        TextDecoration::Underline => (metrics.underline_offset, metrics.underline_size),
+        TextDecoration::LineThrough => (metrics.strikethrough_offset, metrics.strikethrough_size),
+        TextDecoration::Overline => (metrics.ascent, metrics.underline_size),
+    };
+
+    let em = style.resolved_font_size();
+    let rem = style.resolved_root_font_size();
+    let dpi = style.dpi();
+
+    let thickness_dimension = style.text_decoration_thickness();
+    let thickness = if thickness_dimension.is_none() {
+        font_thickness
+    } else {
+        thickness_dimension.as_pixels(em, rem, em, dpi)
+    };
+
+    if thickness <= 0.0 || width <= 0.0 { return None }
+
+    let color = if style.has_text_decoration_color() { style.text_decoration_color() } else { style.color() };
+
+    let decoration_style = match style.text_decoration_style() {
+        TextDecorationStyle::Solid => DecorationStyle::Solid,
+        TextDecorationStyle::Dashed => DecorationStyle::Dashed,
+        TextDecorationStyle::Wavy => DecorationStyle::Wavy,
+        TextDecorationStyle::Double => DecorationStyle::Double,
+    };
+
+    // Offsets are measured from the baseline to the top of the font line (y up).
+    // Custom thicknesses stay centered on the line the font describes.
+    let center_y = baseline_y - offset + font_thickness / 2.0;
+
+    Some(Fragment {
+        bounds: Rect::new(x, center_y - thickness / 2.0, width, thickness),
+        kind: FragmentKind::TextDecoration { color, thickness, style: decoration_style },
+    })
+}
+
+/// Leader filling a tab from start to end, positioned from the top of the line.
+/// Dots and dashes sit on a grid of the inline content so leaders on
+/// different lines line up
+fn leader_fragment(leader: Leader, glyph_run: &GlyphRun<[u8; 4]>, start: f32, end: f32, baseline: f32, line_height: f32) -> Option<Fragment> {
+    let run = glyph_run.run();
+    let brush = glyph_run.style().brush;
+    let metrics = run.metrics();
+
+    let ch = match leader {
+        Leader::None => return None,
+        Leader::Dots => '.',
+        Leader::Dashes => '-',
+        Leader::Line => {
+            if end - start <= 0.0 { return None }
+
+            return Some(Fragment {
+                bounds: Rect::new(start, baseline - metrics.underline_offset, end - start, metrics.underline_size),
+                kind: FragmentKind::TextDecoration {
+                    color: Color(brush[0], brush[1], brush[2], brush[3]),
+                    thickness: metrics.underline_size,
+                    style: DecorationStyle::Solid,
+                },
+            });
+        }
+    };
+
+    let (id, advance) = char_glyph(run, ch)?;
+    let space = char_glyph(run, ' ').map(|(_, space)| space).unwrap_or(advance);
+    let pitch = advance + space;
+
+    let first = (start / pitch).ceil() * pitch;
+    if first + advance > end { return None }
+    let count = ((end - advance - first) / pitch).floor() as usize + 1;
+
+    let style_index = glyph_run.glyphs().last().map(|g| g.style_index).unwrap_or_default();
+    let mut glyphs = vec![Glyph { id, style_index, x: 0.0, y: 0.0, advance: pitch }; count];
+    if let Some(last) = glyphs.last_mut() { last.advance = advance }
+    let width = pitch * (count - 1) as f32 + advance;
+
+    Some(Fragment {
+        bounds: Rect::new(first, 0.0, width, line_height),
+        kind: FragmentKind::Text(GlyphRunFragment {
+            glyphs,
+            baseline,
+            offset: first,
+            advance: width,
+            synthesis: run.synthesis(),
+            font_size: run.font_size(),
+            font: run.font().clone(),
+            normalized_coords: run.normalized_coords().to_vec(),
+            style: glyph_run.style().clone(),
+        }),
+    })
+}
+
+/// Glyph runs of an initial letter starting at x, with its baseline at baseline_y
+fn collect_initial_letter_fragments(letter: &InitialLetter, x: f32, baseline_y: f32, out: &mut Vec<FragmentGroup>) {
+    for line in letter.layout.lines() {
+        let metrics = line.metrics();
+        let baseline = metrics.baseline - metrics.min_coord;
+
+        for item in line.items() {
+            let PositionedLayoutItem::GlyphRun(glyph_run) = item else { continue };
+            let run = glyph_run.run();
+
+            let bounds = Rect {
+                x: x + glyph_run.offset(),
+                y: baseline_y - baseline,
+                width: glyph_run.advance(),
+                height: metrics.line_height,
+            };
+
+            out.push(FragmentGroup {
+                bounds,
+                area: bounds,
+                fragments: vec![Fragment {
+                    bounds,
+                    kind: FragmentKind::Text(GlyphRunFragment {
+                        glyphs: glyph_run.glyphs().collect(),
+                        baseline,
+                        offset: glyph_run.offset(),
+                        advance: glyph_run.advance(),
+                        synthesis: run.synthesis(),
+                        font_size: run.font_size(),
+                        font: run.font().clone(),
+                        normalized_coords: run.normalized_coords().to_vec(),
+                        style: glyph_run.style().clone(),
+                    }),
+                }],
+                subgroups: vec![],
+                splittable: false,
+                opacity: 1.0,
+                rotation: 0.0,
+            });
+        }
+    }
+}
+
+/// Glyphs of a run that lie fully within the start and end of the line.
+/// Returns the offset of the first kept glyph, the glyphs and their advance
+fn visible_glyphs(glyph_run: &GlyphRun<[u8; 4]>, start: f32, end: f32) -> (f32, Vec<Glyph>, f32) {
+    let mut x = glyph_run.offset();
+    let mut offset = None;
+    let mut glyphs = vec![];
+
+    for glyph in glyph_run.glyphs() {
+        let glyph_end = x + glyph.advance;
+
+        if x >= start - 0.01 && glyph_end <= end + 0.01 {
+            offset.get_or_insert(x);
+            glyphs.push(glyph);
+        }
+
+        x = glyph_end;
+    }
+
+    let advance = glyphs.iter().map(|g| g.advance).sum();
+    (offset.unwrap_or(x), glyphs, advance)
+}
+
 pub(super) fn collect_fragments(
     tree: &LayoutTree,
     node_id: NodeId,
@@ -219,27 +476,94 @@ pub(super) fn collect_fragments(
     let node_children = node.children.clone();
 
     if matches!(node.kind, LayoutNodeKind::InlineContent) {
+        // Floats are drawn below the text that wraps around them
+        for &child_id in &node_children {
+            if tree.node_from_id(child_id).style_context.float() != Float::None {
+                collect_fragments(tree, child_id, offset_x + node.final_layout.location.x, offset_y + node.final_layout.location.y, out);
+            }
+        }
+
         if let Some(inline_layout) = &node.inline_layout {
-            for line in inline_layout.lines() {
+            let truncation = node.inline_truncation;
+            let visible_lines = truncation.map(|t| t.lines).unwrap_or(usize::MAX);
+
+            for (line_index, line) in inline_layout.lines().enumerate().take(visible_lines) {
                 let line_metrics = line.metrics();
-                for item in line.items() {
+                let column = node.inline_line_columns.get(line_index).copied().unwrap_or(Point::ZERO);
+                let line_x = node.final_layout.location.x + node.inline_line_shifts.get(line_index).copied().unwrap_or(0.0) + column.x;
+                let line_y = node.final_layout.location.y + column.y;
+
+                // The last visible line of truncated content keeps only the glyphs in the visible range
+                let cut = truncation.filter(|_| line_index + 1 == visible_lines);
+                let visible = cut.and_then(|t| t.visible);
+                let ellipsis = cut.is_some_and(|t| t.ellipsis);
+
+                // Run the ellipsis is drawn after (or before in rtl) and its edge
+                let mut ellipsis_anchor: Option<(GlyphRun<[u8; 4]>, f32)> = None;
+
+                // A line broken at a soft hyphen shows a hyphen after its last glyph run.
+                // An ellipsis replaces it
+                let hyphen = line_hyphen(&node.inline_text, &line).filter(|_| !ellipsis);
+                let last_run = line.items()
+                    .enumerate()
+                    .filter(|(_, item)| matches!(item, PositionedLayoutItem::GlyphRun(_)))
+                    .map(|(i, _)| i)
+                    .last();
+
+                for (item_index, item) in line.items().enumerate() {
                     match item {
                         PositionedLayoutItem::GlyphRun(glyph_run) => {
-                            let x = node.final_layout.location.x + glyph_run.offset();
-                            let y = node.final_layout.location.y + line_metrics.offset + line_metrics.baseline - glyph_run.baseline();
+                            // Run offsets include the alignment offset of the line.
+                            // Fragments start at the top of the line, with the baseline relative to it
+                            let mut offset = glyph_run.offset();
+                            let mut glyphs: Vec<Glyph> = glyph_run.glyphs().collect();
+                            let mut advance = glyph_run.advance();
+
+                            if let Some((start, end)) = visible {
+                                (offset, glyphs, advance) = visible_glyphs(&glyph_run, start, end);
+                                if glyphs.is_empty() { continue }
+
+                                let rtl = inline_layout.is_rtl();
+                                let edge = if rtl { offset } else { offset + advance };
+                                if ellipsis_anchor.as_ref().is_none_or(|(_, anchor)| (rtl && edge < *anchor) || (!rtl && edge > *anchor)) {
+                                    ellipsis_anchor = Some((glyph_run.clone(), edge));
+                                }
+                            }
+
+                            // Decorations and baseline shifts come from the style of the text span the run came from
+                            let run_start = glyph_run.run().text_range().start;
+                            let span_style = node.inline_spans.iter()
+                                .find(|(range, _)| range.contains(&run_start))
+                                .map(|(_, style)| style);
+
+                            let x = line_x + offset;
+                            let y = line_y + line_metrics.min_coord;
+                            let shift = span_style.map(|style| script_baseline_shift(glyph_run.run(), style)).unwrap_or(0.0);
+                            let baseline = glyph_run.baseline() - line_metrics.min_coord + shift;
+
+                            if let Some((id, hyphen_advance)) = hyphen && Some(item_index) == last_run {
+                                glyphs.push(Glyph {
+                                    id,
+                                    style_index: glyphs.last().map(|g| g.style_index).unwrap_or_default(),
+                                    x: 0.0,
+                                    y: 0.0,
+                                    advance: hyphen_advance,
+                                });
+                                advance += hyphen_advance;
+                            }
 
                             let fragment = Fragment {
                                 bounds: Rect {
                                     x: offset_x + x,
                                     y: offset_y + y,
-                                    width: glyph_run.advance(),
+                                    width: advance,
                                     height: line_metrics.line_height,
                                 },
                                 kind: FragmentKind::Text(GlyphRunFragment {
-                                    glyphs: glyph_run.glyphs().collect(),
-                                    baseline: glyph_run.baseline(),
-                                    offset: glyph_run.offset(),
-                                    advance: glyph_run.advance(),
+                                    glyphs,
+                                    baseline,
+                                    offset,
+                                    advance,
                                     synthesis: glyph_run.run().synthesis(),
                                     font_size: glyph_run.run().font_size(),
                                     font: glyph_run.run().font().clone(),
@@ -248,25 +572,127 @@ pub(super) fn collect_fragments(
                                 }),
                             };
 
+                            let bounds = fragment.bounds;
+                            let mut fragments = vec![fragment];
+
+                            if let Some(style) = span_style
+                                && let Some(decoration) = text_decoration_fragment(style, glyph_run.run().metrics(), bounds.x, bounds.y + baseline, bounds.width)
+                            {
+                                fragments.push(decoration);
+                            }
+
                             out.push(FragmentGroup {
-                                bounds: fragment.bounds,
-                                fragments: vec![fragment],
+                                bounds,
+                                area: bounds,
+                                fragments,
                                 subgroups: vec![],
                                 splittable: false,
+                                opacity: 1.0,
+                                rotation: 0.0,
                             });
                         }
 
-                        // TODO this is difficult to get right. We might be missing some information.
+                        // Inline-block layouts are positioned within the inline content
+                        // during inline layout, so we only offset by the inline content
                         PositionedLayoutItem::InlineBox(inline_box) => {
-                            // We need to move the inline box from top aligned to baseline aligned
-                            let effective_height = line_metrics.max_coord - line_metrics.min_coord;
-                            let baseline_offset = (line_metrics.baseline - effective_height) / 2.0;
-                            let baseline_y =  inline_box.y;
-
-                            collect_fragments(tree, NodeId::from(inline_box.id), offset_x + node.final_layout.location.x + inline_box.x, offset_y + node.final_layout.location.y + baseline_y, out);
+                            if is_tab_box(inline_box.id) {
+                                // Leaders take the font of the text before the tab, or after it at the line start
+                                let glyph_runs: Vec<GlyphRun<[u8; 4]>> = line.items()
+                                    .filter_map(|item| match item {
+                                        PositionedLayoutItem::GlyphRun(glyph_run) => Some(glyph_run),
+                                        _ => None,
+                                    })
+                                    .collect();
+                                let glyph_run = glyph_runs.iter()
+                                    .rfind(|glyph_run| glyph_run.offset() <= inline_box.x)
+                                    .or(glyph_runs.first());
+
+                                let start = inline_box.x;
+                                let end = inline_box.x + inline_box.width;
+                                let in_view = visible.is_none_or(|(visible_start, visible_end)| start >= visible_start && end <= visible_end);
+
+                                if in_view && let Some(glyph_run) = glyph_run
+                                    && let Some(mut fragment) = leader_fragment(node.style_context.leader(), glyph_run, start, end, glyph_run.baseline() - line_metrics.min_coord, line_metrics.line_height)
+                                {
+                                    fragment.bounds.x += offset_x + line_x;
+                                    fragment.bounds.y += offset_y + line_y + line_metrics.min_coord;
+
+                                    out.push(FragmentGroup {
+                                        bounds: fragment.bounds,
+                                        area: fragment.bounds,
+                                        fragments: vec![fragment],
+                                        subgroups: vec![],
+                                        splittable: false,
+                                        opacity: 1.0,
+                                        rotation: 0.0,
+                                    });
+                                }
+
+                                continue;
+                            }
+
+                            if is_line_room_box(inline_box.id) {
+                                continue;
+                            }
+
+                            // The letter is drawn with its baseline on the last line it spans
+                            if inline_box.id == INITIAL_LETTER_BOX_ID {
+                                if let Some(letter) = &node.inline_initial_letter {
+                                    let x = offset_x + line_x + inline_box.x;
+                                    let y = offset_y + line_y + letter.baseline(inline_layout);
+                                    collect_initial_letter_fragments(letter, x, y, out);
+                                }
+
+                                continue;
+                            }
+
+                            if let Some((start, end)) = visible && (inline_box.x < start || inline_box.x + inline_box.width > end) {
+                                continue;
+                            }
+
+                            collect_fragments(tree, NodeId::from(inline_box.id), offset_x + line_x, offset_y + line_y, out);
                         }
                     }
                 }
+
+                if ellipsis && let Some((glyph_run, edge)) = ellipsis_anchor {
+                    let style_index = glyph_run.glyphs().last().map(|g| g.style_index).unwrap_or_default();
+                    let glyphs: Vec<Glyph> = ellipsis_glyphs(glyph_run.run()).into_iter()
+                        .map(|(id, advance)| Glyph { id, style_index, x: 0.0, y: 0.0, advance })
+                        .collect();
+                    let advance: f32 = glyphs.iter().map(|g| g.advance).sum();
+                    let offset = if inline_layout.is_rtl() { edge - advance } else { edge };
+
+                    let bounds = Rect {
+                        x: offset_x + line_x + offset,
+                        y: offset_y + line_y + line_metrics.min_coord,
+                        width: advance,
+                        height: line_metrics.line_height,
+                    };
+
+                    out.push(FragmentGroup {
+                        bounds,
+                        area: bounds,
+                        fragments: vec![Fragment {
+                            bounds,
+                            kind: FragmentKind::Text(GlyphRunFragment {
+                                glyphs,
+                                baseline: glyph_run.baseline() - line_metrics.min_coord,
+                                offset,
+                                advance,
+                                synthesis: glyph_run.run().synthesis(),
+                                font_size: glyph_run.run().font_size(),
+                                font: glyph_run.run().font().clone(),
+                                normalized_coords: glyph_run.run().normalized_coords().to_vec(),
+                                style: glyph_run.style().clone(),
+                            }),
+                        }],
+                        subgroups: vec![],
+                        splittable: false,
+                        opacity: 1.0,
+                        rotation: 0.0,
+                    });
+                }
             }
         }
 
@@ -280,10 +706,17 @@ pub(super) fn collect_fragments(
         node.final_layout.size.height,
     ));
 
-    if  let LayoutNodeKind::Container = node.kind
-        && let Some(style_fragments) = container_style_fragments(node, offset_x, offset_y)
-    {
-        group.fragments.extend(style_fragments);
+    if let LayoutNodeKind::Container = node.kind {
+        group.opacity = node.style_context.opacity();
+        group.rotation = node.style_context.rotate();
+
+        if let Some(style_fragments) = container_style_fragments(node, offset_x, offset_y) {
+            group.fragments.extend(style_fragments);
+        }
+    }
+
+    if let LayoutNodeKind::Columns = node.kind {
+        group.fragments.extend(column_rule_fragments(node, offset_x, offset_y));
     }
 
     for child_id in node_children {
@@ -1,8 +1,11 @@
-use crate::layout::tree::{LayoutNodeKind, LayoutTree};
-use crate::styles::context::{FontStyle, StyleContext, TextDecoration, TextTransform, WhiteSpace};
-use parley::{Alignment, AlignmentOptions, FontWeight, InlineBox, LineHeight, StyleProperty};
+use crate::layout::tree::{LayoutNode, LayoutNodeKind, LayoutTree};
+use crate::layout::clears;
+use crate::styles::context::{Clear, Dimension, Direction, Display, Float, FontStyle, FontVariant, Hyphens, StyleContext, TabAlign, TextAlign, TextDecoration, TextOverflow, TextTransform, VerticalAlign, WhiteSpace};
+use parley::swash::FontRef;
+use parley::{Alignment, AlignmentOptions, Cluster, FontFeature, FontSettings, FontVariation, FontWeight, InlineBox, LineHeight, PositionedLayoutItem, Run, StyleProperty};
+use std::borrow::Cow;
 use std::ops::Range;
-use taffy::{LayoutInput, LayoutOutput, LayoutPartialTree, NodeId, Point, Size};
+use taffy::{AvailableSpace, Layout, LayoutInput, LayoutOutput, LayoutPartialTree, Line, NodeId, Point, Rect, RequestedAxis, RunMode, Size, SizingMode};
 use unicode_segmentation::UnicodeSegmentation;
 
 fn parley_style<'a>(style: &StyleContext) -> Vec<StyleProperty<'a, [u8; 4]>> {
@@ -18,12 +21,27 @@ fn parley_style<'a>(style: &StyleContext) -> Vec<StyleProperty<'a, [u8; 4]>> {
 
     let color = style.color();
 
+    // Tags are stored as bytes, swash tags are big endian
+    let mut features: Vec<FontFeature> = style.font_features().iter()
+        .map(|(tag, value)| FontFeature { tag: u32::from_be_bytes(*tag), value: *value })
+        .collect();
+
+    for tag in script_feature(style).into_iter().chain(small_caps_feature(style)) {
+        features.push(FontFeature { tag: u32::from_be_bytes(tag), value: 1 });
+    }
+
+    let variations: Vec<FontVariation> = style.font_variations().iter()
+        .map(|(tag, value)| FontVariation { tag: u32::from_be_bytes(*tag), value: *value })
+        .collect();
+
     vec![
         StyleProperty::FontSize(style.resolved_font_size()),
         StyleProperty::LineHeight(LineHeight::Absolute(style.line_height().as_pixels(em, rem, em, dpi))),
         StyleProperty::LetterSpacing(style.letter_spacing().as_pixels(em, rem, em, dpi)),
         StyleProperty::FontWeight(FontWeight::new(style.font_weight() as f32)),
         StyleProperty::FontStyle(font_style),
+        StyleProperty::FontFeatures(FontSettings::List(Cow::Owned(features))),
+        StyleProperty::FontVariations(FontSettings::List(Cow::Owned(variations))),
         StyleProperty::Brush([color.0, color.1, color.2, color.3]),
         StyleProperty::WordSpacing(style.word_spacing().as_pixels(em, rem, em, dpi)),
         StyleProperty::Underline(matches!(style.text_decoration(), TextDecoration::Underline)),
@@ -31,13 +49,142 @@ fn parley_style<'a>(style: &StyleContext) -> Vec<StyleProperty<'a, [u8; 4]>> {
     ]
 }
 
-enum InlineItemBuilder<'a> {
-    Text{range: Range<usize>, styles: Vec<StyleProperty<'a, [u8; 4]>>},
+/// Super and subscript text is drawn this much smaller when the font has no glyphs for it
+const SYNTHETIC_SCRIPT_SCALE: f32 = 0.65;
+/// Lowercase letters are drawn as capitals this much smaller when the font has no small caps
+const SYNTHETIC_SMALL_CAPS_SCALE: f32 = 0.75;
+
+/// The feature for super and subscript text. Vertical align only applies
+/// to inline spans, text directly in a block stays on the baseline
+fn script_feature(style: &StyleContext) -> Option<[u8; 4]> {
+    if style.display() != Display::Inline { return None }
+
+    match style.vertical_align() {
+        VerticalAlign::Baseline => None,
+        VerticalAlign::Super => Some(*b"sups"),
+        VerticalAlign::Sub => Some(*b"subs"),
+    }
+}
+
+fn small_caps_feature(style: &StyleContext) -> Option<[u8; 4]> {
+    match style.font_variant() {
+        FontVariant::Normal => None,
+        FontVariant::SmallCaps => Some(*b"smcp"),
+    }
+}
+
+/// Whether the font of a run has a substitution for the feature
+fn font_has_feature(run: &Run<[u8; 4]>, tag: [u8; 4]) -> bool {
+    let font = run.font();
+    FontRef::from_index(font.data.as_ref(), font.index as usize)
+        .is_some_and(|font_ref| font_ref.features().any(|feature| feature.tag() == u32::from_be_bytes(tag)))
+}
+
+/// How far a run moves off the baseline, down is positive.
+/// Only synthesized super and subscripts move, fonts place their own script glyphs
+pub(super) fn script_baseline_shift(run: &Run<[u8; 4]>, style: &StyleContext) -> f32 {
+    let Some(tag) = script_feature(style) else { return 0.0 };
+    if font_has_feature(run, tag) { return 0.0 }
+
+    match style.vertical_align() {
+        VerticalAlign::Super => -0.35 * style.resolved_font_size(),
+        VerticalAlign::Sub => 0.15 * style.resolved_font_size(),
+        VerticalAlign::Baseline => 0.0,
+    }
+}
+
+/// Super, sub and small caps text whose font has no glyphs for it is synthesized.
+/// Scripts are drawn smaller and small caps draw lowercase letters as smaller capitals.
+/// The smaller text is added as spans over the spans it resizes, so the original
+/// spans still style the text
+fn synthesize_font_variants(tree: &mut LayoutTree, text: &mut String, items: &mut Vec<InlineItemBuilder>, spans: &mut Vec<(Range<usize>, StyleContext)>) {
+    if !spans.iter().any(|(_, style)| script_feature(style).is_some() || small_caps_feature(style).is_some()) { return }
+
+    // Fonts are picked per run, so lay out once to see which fonts are missing features
+    let mut layout = build_layout(tree, text, items, spans, 1.0);
+    layout.break_all_lines(None);
+    let mut missing = vec![(false, false); spans.len()];
+
+    for line in layout.lines() {
+        for run in line.runs() {
+            // Spans with the same font can share a run
+            let run_range = run.text_range();
+            for (i, (range, style)) in spans.iter().enumerate() {
+                if range.start >= run_range.end || range.end <= run_range.start { continue }
+
+                missing[i].0 |= script_feature(style).is_some_and(|tag| !font_has_feature(&run, tag));
+                missing[i].1 |= small_caps_feature(style).is_some_and(|tag| !font_has_feature(&run, tag));
+            }
+        }
+    }
+
+    for (i, (script, small_caps)) in missing.into_iter().enumerate() {
+        let (range, style) = spans[i].clone();
+        let size = if script { style.resolved_font_size() * SYNTHETIC_SCRIPT_SCALE } else { style.resolved_font_size() };
+        let mut resized = vec![];
+
+        if script {
+            resized.push((range.clone(), size));
+        }
+
+        if small_caps {
+            // Letters whose capital has the same length are replaced in place,
+            // so the text ranges of items don't move
+            let mut capitals = String::with_capacity(range.len());
+            for (j, c) in text[range.clone()].char_indices() {
+                let mut upper = c.to_uppercase();
+                match (upper.next(), upper.next()) {
+                    (Some(u), None) if c.is_lowercase() && u.len_utf8() == c.len_utf8() => {
+                        let start = range.start + j;
+                        match resized.last_mut() {
+                            Some((last, _)) if last.end == start => last.end = start + c.len_utf8(),
+                            _ => resized.push((start..start + c.len_utf8(), size * SYNTHETIC_SMALL_CAPS_SCALE)),
+                        }
+                        capitals.push(u);
+                    }
+                    _ => capitals.push(c),
+                }
+            }
+            text.replace_range(range.clone(), &capitals);
+        }
+
+        for (resized_range, size) in resized {
+            let mut resized_style = style;
+            resized_style.set_resolved_font_size(size);
+            items.push(InlineItemBuilder::Text { span: spans.len() });
+            spans.push((resized_range, resized_style));
+        }
+    }
+}
+
+/// Resolve inline-block margins against the width of the inline container.
+/// Auto margins resolve to zero
+fn inline_block_margin(style: &StyleContext, container_width: f32) -> Rect<f32> {
+    let em = style.resolved_font_size();
+    let rem = style.resolved_root_font_size();
+    let dpi = style.dpi();
+
+    Rect {
+        left: style.margin_left().as_pixels(container_width, rem, em, dpi),
+        right: style.margin_right().as_pixels(container_width, rem, em, dpi),
+        top: style.margin_top().as_pixels(container_width, rem, em, dpi),
+        bottom: style.margin_bottom().as_pixels(container_width, rem, em, dpi),
+    }
+}
+
+/// Text items index into the styled spans of the inline content
+enum InlineItemBuilder {
+    Text{span: usize},
     Inline{id: NodeId, index: usize, width: f32, height: f32},
+    Tab{id: u64, index: usize},
+    /// Space kept on a line for an initial letter or beside floats
+    Room{id: u64, index: usize, width: f32},
+    /// Where a float was in the text, floats are placed from the line it falls on
+    Float{id: NodeId, index: usize},
 }
 
 /// Transform a string, preserving whitespace and performing text transformations
-fn transform_with_ws(
+pub(super) fn transform_with_ws(
     text: &str,
     preserve_whitespace: WhiteSpace,
     text_transform: TextTransform,
@@ -85,7 +232,38 @@ fn transform_with_ws(
                 result.push(' ');
             }
         }
-        WhiteSpace::Pre | WhiteSpace::PreWrap | WhiteSpace::PreLine => {
+        WhiteSpace::PreLine => {
+            // Spaces and tabs collapse, newlines are kept as forced breaks
+            let mut pending_space = allow_pre_ws && transformed.starts_with([' ', '\t']);
+
+            for c in transformed.replace("\r\n", "\n").chars() {
+                if c == '\n' {
+                    result.push('\n');
+                    pending_space = false;
+                } else if c.is_whitespace() {
+                    pending_space = true;
+                } else {
+                    if pending_space && !result.ends_with('\n') {
+                        result.push(' ');
+                    }
+                    pending_space = false;
+                    result.push(c);
+                }
+            }
+
+            if pending_space && !result.is_empty() && !result.ends_with('\n') {
+                result.push(' ');
+            }
+
+            if !allow_pre_ws {
+                result = result.trim_start().to_string();
+            }
+
+            trailing_ws = result.ends_with(char::is_whitespace);
+        }
+        WhiteSpace::Pre | WhiteSpace::PreWrap => {
+            let transformed = transformed.replace("\r\n", "\n");
+
             if !allow_pre_ws {
                 result = transformed.trim_start().to_string();
             } else {
@@ -102,41 +280,1121 @@ fn transform_with_ws(
     (result, trailing_ws)
 }
 
+/// Transform a string like `transform_with_ws`, but whitespace holding a tab
+/// collapses to a single tab instead of a space. Used when tab stops are set
+pub(super) fn transform_keeping_tabs(
+    text: &str,
+    preserve_whitespace: WhiteSpace,
+    text_transform: TextTransform,
+    allow_pre_ws: bool,
+) -> (String, bool) {
+    let mut result = String::new();
+    let mut trailing_ws = false;
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let (piece, tab_run) = match rest.find('\t') {
+            Some(tab) => {
+                let start = rest[..tab].trim_end_matches([' ', '\r', '\n']).len();
+                let end = rest[tab..].find(|c: char| !c.is_whitespace()).map(|i| tab + i).unwrap_or(rest.len());
+                (&rest[..start], Some(end))
+            }
+            None => (rest, None),
+        };
+
+        let (transformed, piece_trailing_ws) = transform_with_ws(piece, preserve_whitespace, text_transform, allow_pre_ws && result.is_empty());
+        result.push_str(&transformed);
+        trailing_ws = piece_trailing_ws;
+
+        match tab_run {
+            Some(end) => {
+                result.push('\t');
+                trailing_ws = true;
+                rest = &rest[end..];
+            }
+            None => break,
+        }
+    }
+
+    (result, trailing_ws)
+}
+
+/// Replace straight quotes with curly quotes, `--` and `---` with en and em dashes
+/// and `...` with an ellipsis. Quotes open after whitespace, opening brackets,
+/// dashes and other opening quotes, `previous` is the character before the text
+pub(super) fn smart_punctuation(text: &str, mut previous: Option<char>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let opening = previous.is_none_or(|p| p.is_whitespace() || matches!(p, '(' | '[' | '{' | '\u{2013}' | '\u{2014}' | '\u{201C}' | '\u{2018}'));
+
+        let replaced = match c {
+            '"' if opening => '\u{201C}',
+            '"' => '\u{201D}',
+            '\'' if opening => '\u{2018}',
+            '\'' => '\u{2019}',
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                if chars.next_if_eq(&'-').is_some() { '\u{2014}' } else { '\u{2013}' }
+            }
+            '.' if chars.peek() == Some(&'.') => {
+                let mut dots = chars.clone();
+                dots.next();
+                if dots.next() == Some('.') {
+                    chars.next();
+                    chars.next();
+                    ELLIPSIS
+                } else {
+                    '.'
+                }
+            }
+            _ => c,
+        };
+
+        out.push(replaced);
+        previous = Some(replaced);
+    }
+
+    out
+}
+
+/// Expand tabs to spaces, advancing to the next multiple of tab_size.
+/// Column is the number of characters already on the current line.
+pub(super) fn expand_tabs(text: &str, tab_size: usize, mut column: usize) -> String {
+    let mut expanded = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\t' if tab_size > 0 => {
+                let spaces = tab_size - (column % tab_size);
+                expanded.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            }
+            '\t' => {}
+            '\n' => {
+                expanded.push(c);
+                column = 0;
+            }
+            _ => {
+                expanded.push(c);
+                column += 1;
+            }
+        }
+    }
+
+    expanded
+}
+
+pub(super) const SOFT_HYPHEN: char = '\u{AD}';
+
+/// Parley takes the paragraph direction from the first strong character,
+/// so an explicit direction starts the text with an invisible mark
+const LTR_MARK: char = '\u{200E}';
+const RTL_MARK: char = '\u{200F}';
+
+fn to_parley_alignment(text_align: TextAlign) -> Alignment {
+    match text_align {
+        TextAlign::Left => Alignment::Left,
+        TextAlign::Right => Alignment::Right,
+        TextAlign::Center => Alignment::Middle,
+        TextAlign::Justify => Alignment::Justified,
+        TextAlign::Start => Alignment::Start,
+        TextAlign::End => Alignment::End,
+        // Lines are shifted onto the decimal character after layout
+        TextAlign::Decimal(_) => Alignment::Left,
+    }
+}
+
+/// Insert soft hyphens at the hyphenation points of every word.
+/// Words that already contain soft hyphens keep the manual breaks
+pub(super) fn hyphenate(text: &str, lang: hypher::Lang) -> String {
+    let mut hyphenated = String::with_capacity(text.len());
+
+    for word in text.split_word_bounds() {
+        if word.contains(SOFT_HYPHEN) || !word.chars().all(char::is_alphabetic) {
+            hyphenated.push_str(word);
+            continue;
+        }
+
+        for (i, syllable) in hypher::hyphenate(word, lang).enumerate() {
+            if i > 0 { hyphenated.push(SOFT_HYPHEN); }
+            hyphenated.push_str(syllable);
+        }
+    }
+
+    hyphenated
+}
+
+/// Find the glyph and advance for a character in the font of a run.
+/// Used for glyphs we add after shaping, like the hyphen at a soft hyphen break
+pub(super) fn char_glyph(run: &Run<[u8; 4]>, ch: char) -> Option<(u16, f32)> {
+    let font = run.font();
+    let font_ref = FontRef::from_index(font.data.as_ref(), font.index as usize)?;
+
+    let glyph_id = font_ref.charmap().map(ch);
+    if glyph_id == 0 { return None }
+
+    let advance = font_ref
+        .glyph_metrics(run.normalized_coords())
+        .scale(run.font_size())
+        .advance_width(glyph_id);
+
+    Some((glyph_id, advance))
+}
+
+/// The hyphen glyph added to a line that breaks at a soft hyphen.
+/// Lines that don't break at a soft hyphen have no hyphen
+pub(super) fn line_hyphen(text: &str, line: &parley::Line<[u8; 4]>) -> Option<(u16, f32)> {
+    let end = line.text_range().end;
+    if end >= text.len() || !text[..end].ends_with(SOFT_HYPHEN) { return None }
+
+    let run = line.runs().last()?;
+    char_glyph(&run, '-')
+}
+
+pub(super) const ELLIPSIS: char = '\u{2026}';
+
+/// Glyphs for the ellipsis at the end of a truncated line, in the font of a run.
+/// Fonts without an ellipsis glyph get three full stops
+pub(super) fn ellipsis_glyphs(run: &Run<[u8; 4]>) -> Vec<(u16, f32)> {
+    if let Some(glyph) = char_glyph(run, ELLIPSIS) {
+        return vec![glyph];
+    }
+
+    char_glyph(run, '.').map(|glyph| vec![glyph; 3]).unwrap_or_default()
+}
+
+/// Inline content cut by lineClamp or textOverflow
+#[derive(Debug, Clone, Copy)]
+pub(super) struct InlineTruncation {
+    /// Number of lines that stay visible
+    pub(super) lines: usize,
+    /// Horizontal range the glyphs of the last visible line are kept in
+    pub(super) visible: Option<(f32, f32)>,
+    /// Whether an ellipsis follows the kept glyphs
+    pub(super) ellipsis: bool,
+}
+
+/// Find how the laid out lines are cut.
+/// Lines past the clamp are hidden and the last visible line is cut
+/// to the container width, leaving room for the ellipsis
+fn truncate_lines(layout: &parley::Layout<[u8; 4]>, style: &StyleContext, width: Option<f32>) -> Option<InlineTruncation> {
+    let overflow = style.text_overflow();
+
+    // nowrap only ever shows one line when the overflow is handled
+    let max_lines = match (style.line_clamp(), style.white_space()) {
+        (0, WhiteSpace::NoWrap) if overflow != TextOverflow::Visible => 1,
+        (0, _) => return None,
+        (clamp, _) => clamp as usize,
+    };
+
+    let lines = layout.len().min(max_lines);
+    let clamped = lines < layout.len();
+    let last = layout.get(lines.checked_sub(1)?)?;
+    let metrics = last.metrics();
+
+    let width = width.filter(|w| w.is_finite()).unwrap_or(f32::INFINITY);
+    let overflows = metrics.advance - metrics.trailing_whitespace > width;
+
+    if !clamped && !overflows { return None }
+
+    let rtl = layout.is_rtl();
+
+    let (visible, ellipsis) = match overflow {
+        TextOverflow::Visible => (None, false),
+        TextOverflow::Clip => (Some((0.0, width)), false),
+        TextOverflow::Ellipsis => {
+            let ellipsis_advance: f32 = last.runs().last()
+                .map(|run| ellipsis_glyphs(&run).iter().map(|(_, advance)| advance).sum())
+                .unwrap_or(0.0);
+
+            // The ellipsis follows the content, so trailing whitespace is dropped as well
+            let visible = if rtl {
+                (ellipsis_advance.max(metrics.offset + metrics.trailing_whitespace), width)
+            } else {
+                (0.0, (width - ellipsis_advance).min(metrics.offset + metrics.advance - metrics.trailing_whitespace))
+            };
+
+            (Some(visible), true)
+        }
+    };
+
+    Some(InlineTruncation { lines, visible, ellipsis })
+}
+
+/// Build the parley layout for the collected inline text.
+/// Text styles are built with their font size scaled, this is how `fontSize: fit`
+/// tries sizes without cascading again
+fn build_layout(tree: &mut LayoutTree, text: &str, items: &[InlineItemBuilder], spans: &[(Range<usize>, StyleContext)], scale: f32) -> parley::Layout<[u8; 4]> {
+    let mut builder = tree.context.parley_layout_context
+        .ranged_builder(&mut tree.context.parley_font_context, text, tree.context.parley_display_scale, true);
+
+    for item in items {
+        match item {
+            InlineItemBuilder::Inline { id, index, width, height } => {
+                builder.push_inline_box(InlineBox{
+                    id: (*id).into(), width: *width, height: *height, index: *index
+                });
+            },
+            InlineItemBuilder::Tab { id, index } => {
+                builder.push_inline_box(InlineBox{
+                    id: *id, width: 0.0, height: 0.0, index: *index
+                });
+            },
+            InlineItemBuilder::Room { id, index, width } => {
+                builder.push_inline_box(InlineBox{
+                    id: *id, width: *width, height: 0.0, index: *index
+                });
+            },
+            InlineItemBuilder::Float { .. } => {},
+            InlineItemBuilder::Text { span } => {
+                let (range, mut style) = spans[*span].clone();
+                style.set_resolved_font_size(style.resolved_font_size() * scale);
+
+                for s in parley_style(&style) {
+                    builder.push(s, range.clone());
+                }
+            }
+        }
+    }
+
+    builder.build(text)
+}
+
+/// Inline box ids of tabs start here, below are inline-block node ids
+pub(super) const TAB_BOX_ID: u64 = 1 << 48;
+
+pub(super) fn is_tab_box(id: u64) -> bool {
+    id >= TAB_BOX_ID
+}
+
+/// Line breaking settings shared by every layout of the inline content
+#[derive(Clone)]
+struct LineBreaking {
+    wrap_width: Option<f32>,
+    /// Tab stop positions in pixels, sorted
+    tab_stops: Vec<(f32, TabAlign)>,
+    /// Tabs past the last stop advance to multiples of this many spaces
+    tab_size: f32,
+    /// How much narrower than the wrap width the first lines are
+    line_indents: Vec<f32>,
+    /// Width floats are placed within
+    container_width: f32,
+}
+
+/// Width of a tab starting at `start` that aligns the segment after it.
+/// Stops the segment doesn't fit before are skipped
+fn tab_width(start: f32, segment: f32, decimal: Option<f32>, tab_stops: &[(f32, TabAlign)], tab_interval: f32) -> f32 {
+    for &(position, align) in tab_stops {
+        let width = position - start - match align {
+            TabAlign::Left => 0.0,
+            TabAlign::Right => segment,
+            TabAlign::Center => segment / 2.0,
+            TabAlign::Decimal => decimal.unwrap_or(segment),
+        };
+
+        if position > start + 0.01 && width >= 0.0 {
+            return width;
+        }
+    }
+
+    if tab_interval > 0.0 {
+        ((start / tab_interval).floor() + 1.0) * tab_interval - start
+    } else {
+        0.0
+    }
+}
+
+/// Find the width of every tab box for the current line breaks.
+/// A segment is the text between a tab and the next tab or the end of the line
+fn tab_widths(layout: &parley::Layout<[u8; 4]>, text: &str, breaking: &LineBreaking) -> Vec<(u64, f32)> {
+    let mut widths = vec![];
+
+    for line in layout.lines() {
+        let metrics = line.metrics();
+        let line_end = metrics.advance - metrics.trailing_whitespace;
+
+        // Tabs with their position from the start of the line, width and text index
+        let tabs: Vec<(u64, f32, f32, usize)> = line.items()
+            .filter_map(|item| match item {
+                PositionedLayoutItem::InlineBox(inline_box) if is_tab_box(inline_box.id) => {
+                    let index = layout.inline_boxes().iter().find(|b| b.id == inline_box.id)?.index;
+                    Some((inline_box.id, inline_box.x - metrics.offset, inline_box.width, index))
+                }
+                _ => None,
+            })
+            .collect();
+
+        if tabs.is_empty() { continue }
+
+        let tab_interval = line.runs().next()
+            .and_then(|run| char_glyph(&run, ' '))
+            .map(|(_, advance)| advance * breaking.tab_size)
+            .unwrap_or(0.0);
+
+        // Earlier tabs on the line move the tabs after them
+        let mut shift = 0.0;
+
+        for (i, &(id, x, width, index)) in tabs.iter().enumerate() {
+            let after = x + width;
+            let (segment_end, text_end) = tabs.get(i + 1)
+                .map(|&(_, next_x, _, next_index)| (next_x, next_index))
+                .unwrap_or((line_end, line.text_range().end));
+
+            let decimal = text[index..text_end].find('.')
+                .and_then(|i| Cluster::from_byte_index(layout, index + i))
+                .and_then(|cluster| cluster.visual_offset())
+                .map(|decimal_x| decimal_x - metrics.offset - after);
+
+            let new_width = tab_width(x + shift, (segment_end - after).max(0.0), decimal, &breaking.tab_stops, tab_interval);
+            shift += new_width - width;
+            widths.push((id, new_width));
+        }
+    }
+
+    widths
+}
+
+/// Inline content a cell lines up on its decimal character,
+/// either the content itself or the only child of its containers
+fn decimal_content(tree: &LayoutTree, node_id: NodeId) -> Option<(NodeId, char)> {
+    let node = tree.node_from_id(node_id);
+
+    match node.kind {
+        LayoutNodeKind::InlineContent => match node.style_context.text_align() {
+            TextAlign::Decimal(decimal) => Some((node_id, decimal)),
+            _ => None,
+        },
+        LayoutNodeKind::Container | LayoutNodeKind::Columns => match node.children.as_slice() {
+            [child] => decimal_content(tree, *child),
+            _ => None,
+        },
+        LayoutNodeKind::Text => None,
+    }
+}
+
+fn is_flex_row(node: &LayoutNode) -> bool {
+    node.style.display == taffy::Display::Flex
+        && matches!(node.style.flex_direction, taffy::FlexDirection::Row | taffy::FlexDirection::RowReverse)
+}
+
+/// Width before and after the decimal character of each line.
+/// Lines without one end where the character would be
+fn decimal_line_widths(node: &LayoutNode, decimal: char) -> Vec<(f32, f32)> {
+    let Some(layout) = &node.inline_layout else { return vec![] };
+
+    layout.lines()
+        .map(|line| {
+            let metrics = line.metrics();
+            let content_width = metrics.advance - metrics.trailing_whitespace;
+            let range = line.text_range();
+
+            let before = node.inline_text.get(range.clone())
+                .and_then(|text| text.find(decimal))
+                .and_then(|i| Cluster::from_byte_index(layout, range.start + i))
+                .and_then(|cluster| cluster.visual_offset())
+                .map(|x| x - metrics.offset)
+                .unwrap_or(content_width);
+
+            (before, content_width - before)
+        })
+        .collect()
+}
+
+/// Groups cells that line up together. Cells stacked in a block or flex column
+/// form a group, as do the cells at the same index of flex rows stacked in a container.
+/// Every cell belongs to the first group that claims it
+fn collect_decimal_groups(tree: &LayoutTree, node_id: NodeId, grouped: &mut Vec<NodeId>, groups: &mut Vec<Vec<(NodeId, char)>>) {
+    let node = tree.node_from_id(node_id);
+
+    let mut push_group = |cells: Vec<(NodeId, char)>| {
+        let cells: Vec<(NodeId, char)> = cells.into_iter().filter(|(id, _)| !grouped.contains(id)).collect();
+        if cells.is_empty() { return }
+        grouped.extend(cells.iter().map(|(id, _)| *id));
+        groups.push(cells);
+    };
+
+    let rows: Vec<&[NodeId]> = node.children.iter()
+        .map(|&child| tree.node_from_id(child))
+        .filter(|child| is_flex_row(child))
+        .map(|row| row.children.as_slice())
+        .collect();
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+    for column in 0..columns {
+        push_group(rows.iter()
+            .filter_map(|row| row.get(column))
+            .filter_map(|&cell| decimal_content(tree, cell))
+            .collect());
+    }
+
+    if !is_flex_row(node) {
+        push_group(node.children.iter().filter_map(|&child| decimal_content(tree, child)).collect());
+    }
+
+    // Content without sibling cells lines up on its own
+    if let Some(content) = decimal_content(tree, node_id).filter(|(id, _)| *id == node_id) {
+        push_group(vec![content]);
+    }
+
+    for &child in &node.children {
+        collect_decimal_groups(tree, child, grouped, groups);
+    }
+}
+
+/// Shift the lines of `textAlign: decimal` content so the decimal characters
+/// of sibling cells line up. The widest part after the character decides how
+/// far right the column sits, the widest part before it keeps lines in view
+pub(super) fn align_decimal_columns(tree: &mut LayoutTree, root: NodeId) {
+    let mut groups = vec![];
+    collect_decimal_groups(tree, root, &mut vec![], &mut groups);
+
+    for cells in groups {
+        let widths: Vec<Vec<(f32, f32)>> = cells.iter()
+            .map(|&(id, decimal)| decimal_line_widths(tree.node_from_id(id), decimal))
+            .collect();
+        let max_before = widths.iter().flatten().map(|(before, _)| *before).fold(0.0, f32::max);
+        let max_after = widths.iter().flatten().map(|(_, after)| *after).fold(0.0, f32::max);
+        let width = cells.iter()
+            .map(|&(id, _)| tree.node_from_id(id).final_layout.size.width)
+            .fold(f32::INFINITY, f32::min);
+
+        let position = (width - max_after).max(max_before);
+
+        for (&(id, _), lines) in cells.iter().zip(widths) {
+            tree.node_from_id_mut(id).inline_line_shifts = lines.iter()
+                .map(|(before, _)| position - before)
+                .collect();
+        }
+    }
+}
+
+/// Break lines and size the tabs on them.
+/// Tab widths change the line breaks, so this repeats until the tabs settle
+fn break_lines_with_tabs(layout: &mut parley::Layout<[u8; 4]>, text: &str, wrap_width: Option<f32>, breaking: &LineBreaking) {
+    break_lines(layout, wrap_width, &breaking.line_indents);
+
+    if !layout.inline_boxes().iter().any(|b| is_tab_box(b.id)) { return }
+
+    for _ in 0..4 {
+        let widths = tab_widths(layout, text, breaking);
+        let mut changed = false;
+
+        for inline_box in layout.inline_boxes_mut() {
+            if let Some((_, width)) = widths.iter().find(|(id, _)| *id == inline_box.id)
+                && (inline_box.width - width).abs() > 0.01
+            {
+                inline_box.width = *width;
+                changed = true;
+            }
+        }
+
+        if !changed { break }
+        break_lines(layout, wrap_width, &breaking.line_indents);
+    }
+}
+
+/// Break lines at the wrap width, with the first lines narrowed by their indents
+fn break_lines(layout: &mut parley::Layout<[u8; 4]>, wrap_width: Option<f32>, line_indents: &[f32]) {
+    match wrap_width {
+        Some(wrap_width) if wrap_width.is_finite() && !line_indents.is_empty() => {
+            let mut lines = layout.break_lines();
+            let mut line = 0;
+            while lines.break_next(wrap_width - line_indents.get(line).copied().unwrap_or(0.0)).is_some() {
+                line += 1;
+            }
+            lines.finish();
+        }
+        _ => layout.break_all_lines(wrap_width),
+    }
+}
+
+/// Break the layout into lines of the wrap width
+fn break_layout(layout: &mut parley::Layout<[u8; 4]>, text: &str, breaking: &LineBreaking) {
+    let wrap_width = breaking.wrap_width;
+    break_lines_with_tabs(layout, text, wrap_width, breaking);
+
+    // Lines broken at a soft hyphen get a hyphen appended when collecting fragments.
+    // If one no longer fits we break again with room left for the hyphen
+    if let Some(width) = wrap_width && width.is_finite() {
+        let hyphen_overflow = layout.lines()
+            .filter_map(|line| line_hyphen(text, &line).map(|(_, advance)| (line.metrics().advance, advance)))
+            .filter(|(line_advance, advance)| line_advance + advance > width)
+            .map(|(_, advance)| advance)
+            .fold(0.0f32, f32::max);
+
+        if hyphen_overflow > 0.0 {
+            break_lines_with_tabs(layout, text, Some(width - hyphen_overflow), breaking);
+        }
+    }
+}
+
+/// Inline box id that marks where the initial letter is drawn
+pub(super) const INITIAL_LETTER_BOX_ID: u64 = 1 << 47;
+
+/// Inline box ids of the room kept at the start and end of lines
+const LINE_ROOM_BOX_ID: u64 = 1 << 46;
+
+pub(super) fn is_line_room_box(id: u64) -> bool {
+    (LINE_ROOM_BOX_ID..INITIAL_LETTER_BOX_ID).contains(&id)
+}
+
+/// Space between an initial letter and the text beside it, in ems of the text
+const INITIAL_LETTER_GAP: f32 = 0.25;
+
+/// Cap height in ems for fonts that don't report one
+const FALLBACK_CAP_HEIGHT: f32 = 0.7;
+
+/// The enlarged first letter of a block
+#[derive(Clone)]
+pub(super) struct InitialLetter {
+    pub(super) layout: parley::Layout<[u8; 4]>,
+    /// Lines the letter spans, its baseline sits on the last one
+    pub(super) lines: usize,
+    /// Height of the lines beside the letter
+    pub(super) line_height: f32,
+    /// Room the letter takes at the start of the lines beside it
+    width: f32,
+}
+
+impl InitialLetter {
+    /// Baseline of the letter in the inline layout, the baseline of its last line
+    pub(super) fn baseline(&self, layout: &parley::Layout<[u8; 4]>) -> f32 {
+        let first = layout.lines().next().map(|line| line.metrics().baseline).unwrap_or(0.0);
+        layout.get(self.lines - 1)
+            .map(|line| line.metrics().baseline)
+            .unwrap_or(first + (self.lines - 1) as f32 * self.line_height)
+    }
+
+    /// Bottom of the letter in the inline layout
+    pub(super) fn bottom(&self, layout: &parley::Layout<[u8; 4]>) -> f32 {
+        let descent = self.layout.lines().next()
+            .map(|line| line.metrics().max_coord - line.metrics().baseline)
+            .unwrap_or(0.0);
+        self.baseline(layout) + descent
+    }
+}
+
+fn build_initial_letter(tree: &mut LayoutTree, letter: &str, mut style: StyleContext, font_size: f32) -> parley::Layout<[u8; 4]> {
+    style.set_resolved_font_size(font_size);
+    let mut layout = build_layout(tree, letter, &[InlineItemBuilder::Text { span: 0 }], &[(0..letter.len(), style)], 1.0);
+    layout.break_all_lines(None);
+    layout.align(None, Alignment::Start, AlignmentOptions::default());
+    layout
+}
+
+/// Take the first letter out of the text and lay it out to span `lines` lines.
+/// Its cap height lines up with the first line and its baseline sits on the last.
+/// Punctuation before the letter, like an opening quote, is enlarged with it.
+/// An inline box takes the letter's place and the room it needs on the first line
+fn take_initial_letter(
+    tree: &mut LayoutTree,
+    lines: u16,
+    text: &mut String,
+    items: &mut [InlineItemBuilder],
+    spans: &mut [(Range<usize>, StyleContext)],
+) -> Option<(InitialLetter, InlineItemBuilder)> {
+    let start = text.char_indices().find(|(_, c)| !c.is_whitespace() && !matches!(c, &LTR_MARK | &RTL_MARK))?.0;
+
+    // Content starting with an inline box has no first letter
+    let box_first = items.iter().any(|item| match item {
+        InlineItemBuilder::Inline { index, .. } | InlineItemBuilder::Tab { index, .. } => *index <= start,
+        _ => false,
+    });
+    if box_first { return None }
+
+    let mut end = start;
+    for (i, grapheme) in text[start..].grapheme_indices(true) {
+        if grapheme.chars().any(char::is_whitespace) { break }
+        end = start + i + grapheme.len();
+        if grapheme.chars().any(char::is_alphanumeric) { break }
+    }
+
+    let (_, style) = spans.iter().find(|(range, _)| range.contains(&start))?.clone();
+    let letter = text[start..end].to_string();
+
+    // Sized from the cap height of the letter's font
+    let em = style.resolved_font_size();
+    let line_height = style.line_height().as_pixels(em, style.resolved_root_font_size(), em, style.dpi());
+    let probe = build_initial_letter(tree, &letter, style, em);
+    let cap_height = probe.lines().next().and_then(|line| line.runs().next()).and_then(|run| {
+        let font = run.font();
+        let metrics = FontRef::from_index(font.data.as_ref(), font.index as usize)?.metrics(run.normalized_coords());
+        Some(metrics.cap_height / metrics.units_per_em as f32)
+    }).filter(|cap_height| *cap_height > 0.0).unwrap_or(FALLBACK_CAP_HEIGHT);
+
+    let font_size = em + (lines - 1) as f32 * line_height / cap_height;
+    let layout = build_initial_letter(tree, &letter, style, font_size);
+    let width = layout.width() + INITIAL_LETTER_GAP * em;
+
+    // Indices after the letter move back by its length
+    let len = end - start;
+    let shift = |index: usize| if index >= end { index - len } else { index.min(start) };
+    text.replace_range(start..end, "");
+
+    for (range, _) in spans.iter_mut() {
+        *range = shift(range.start)..shift(range.end);
+    }
+    for item in items.iter_mut() {
+        match item {
+            InlineItemBuilder::Inline { index, .. } | InlineItemBuilder::Tab { index, .. }
+            | InlineItemBuilder::Room { index, .. } | InlineItemBuilder::Float { index, .. } => *index = shift(*index),
+            InlineItemBuilder::Text { .. } => {}
+        }
+    }
+
+    let letter = InitialLetter { layout, lines: lines as usize, line_height, width };
+    let letter_box = InlineItemBuilder::Room { id: INITIAL_LETTER_BOX_ID, index: start, width };
+    Some((letter, letter_box))
+}
+
+/// A float with its margin box placed in the inline content
+#[derive(Clone)]
+struct FloatBox {
+    id: NodeId,
+    side: Float,
+    clear: Clear,
+    /// Text index the float was at
+    index: usize,
+    size: Size<f32>,
+    margin: Rect<f32>,
+    x: f32,
+    y: f32,
+}
+
+impl FloatBox {
+    fn width(&self) -> f32 {
+        self.size.width + self.margin.left + self.margin.right
+    }
+
+    fn bottom(&self) -> f32 {
+        self.y + self.size.height + self.margin.top + self.margin.bottom
+    }
+
+    /// Whether the float is beside any part of the band from top to bottom
+    fn beside(&self, top: f32, bottom: f32) -> bool {
+        self.y < bottom && self.bottom() > top
+    }
+}
+
+/// Place floats from the top of the line they fall on, beside the earlier floats
+/// on their side. Floats never go above earlier ones or where they were placed before,
+/// go below the floats they clear and move down when there is no room beside the
+/// floats already there
+fn place_floats(floats: &mut [FloatBox], layout: Option<&parley::Layout<[u8; 4]>>, width: f32) {
+    for i in 0..floats.len() {
+        let (placed, rest) = floats.split_at_mut(i);
+        let float = &mut rest[0];
+
+        let line_top = layout
+            .and_then(|layout| layout.lines().find(|line| line.text_range().end > float.index).or(layout.lines().last()))
+            .map_or(0.0, |line| line.metrics().min_coord);
+
+        let mut top = placed.iter()
+            .filter(|other| clears(float.clear, other.side))
+            .map(|other| other.bottom())
+            .fold(line_top.max(float.y), f32::max);
+        top = placed.iter().map(|other| other.y).fold(top, f32::max);
+
+        let height = float.size.height + float.margin.top + float.margin.bottom;
+
+        loop {
+            let beside: Vec<&FloatBox> = placed.iter().filter(|other| other.beside(top, top + height.max(0.01))).collect();
+            let left = beside.iter().filter(|other| other.side == Float::Left).map(|other| other.x + other.width()).fold(0.0, f32::max);
+            let right = beside.iter().filter(|other| other.side == Float::Right).map(|other| other.x).fold(width, f32::min);
+
+            if beside.is_empty() || right - left >= float.width() {
+                float.x = match float.side {
+                    Float::Right => right - float.width(),
+                    _ => left,
+                };
+                float.y = top;
+                break;
+            }
+
+            top = beside.iter().map(|other| other.bottom()).fold(f32::INFINITY, f32::min);
+        }
+    }
+}
+
+/// Room kept at the start and end of each line, for floats beside the line
+/// and an initial letter on the lines after its first. Lines past the last
+/// one that needs room are left out
+fn line_room(layout: &parley::Layout<[u8; 4]>, floats: &[FloatBox], letter: Option<&InitialLetter>, width: f32) -> Vec<(f32, f32)> {
+    let mut room: Vec<(f32, f32)> = layout.lines().enumerate()
+        .map(|(line_index, line)| {
+            let metrics = line.metrics();
+            let beside = floats.iter().filter(|float| float.beside(metrics.min_coord, metrics.max_coord));
+
+            let mut start = 0.0;
+            let mut end = 0.0;
+            for float in beside {
+                match float.side {
+                    Float::Right => end = f32::max(end, width - float.x),
+                    _ => start = f32::max(start, float.x + float.width()),
+                }
+            }
+
+            if let Some(letter) = letter && line_index > 0 && line_index < letter.lines {
+                start += letter.width;
+            }
+
+            (start, end)
+        })
+        .collect();
+
+    while room.last().is_some_and(|&(start, end)| start <= 0.0 && end <= 0.0) {
+        room.pop();
+    }
+
+    room
+}
+
+/// Passes of breaking lines narrower around floats before their room is taken as settled.
+/// Floats only move down as the lines beside them narrow, so this takes a couple of passes.
+/// The cap only stops floats that keep pushing each other down, the last room is kept then
+const MAX_ROOM_PASSES: usize = 6;
+
+/// Break lines narrower by the room they keep, until the floats they fall on stay put.
+/// Returns the room at the start and end of each line
+fn settle_line_room(
+    layout: &mut parley::Layout<[u8; 4]>,
+    text: &str,
+    breaking: &mut LineBreaking,
+    letter: Option<&InitialLetter>,
+    floats: &mut [FloatBox],
+) -> Vec<(f32, f32)> {
+    let width = breaking.container_width;
+    let mut room = vec![];
+
+    for _ in 0..MAX_ROOM_PASSES {
+        place_floats(floats, Some(layout), width);
+        let next = line_room(layout, floats, letter, width);
+        if next == room { break }
+
+        room = next;
+        breaking.line_indents = room.iter().map(|(start, end)| start + end).collect();
+        break_layout(layout, text, breaking);
+    }
+
+    room
+}
+
+/// Break lines around floats and the initial letter.
+///
+/// Lines are first broken narrower by the room they keep, until the floats they fall
+/// on stay put. Each of those lines then gets inline boxes as wide as the room at its
+/// start and end, and the lines are broken again at exactly their width, so the boxes
+/// can't move to the line before them. Alignment and justification then treat the room
+/// as content
+fn break_lines_with_room(
+    tree: &mut LayoutTree,
+    text: &str,
+    items: &mut Vec<InlineItemBuilder>,
+    spans: &[(Range<usize>, StyleContext)],
+    breaking: &mut LineBreaking,
+    letter: Option<&InitialLetter>,
+    floats: &mut [FloatBox],
+) -> parley::Layout<[u8; 4]> {
+    let mut layout = build_layout(tree, text, items, spans, 1.0);
+    break_layout(&mut layout, text, breaking);
+    if letter.is_none() && floats.is_empty() { return layout }
+
+    let room = settle_line_room(&mut layout, text, breaking, letter, floats);
+
+    let wrap_width = breaking.wrap_width.filter(|width| width.is_finite());
+    let mut line_indents = vec![];
+    let mut room_boxes = vec![];
+
+    for (line_index, (line, (start, end))) in layout.lines().zip(room).enumerate() {
+        let range = line.text_range();
+        let mut advance = line.metrics().advance;
+        let id = LINE_ROOM_BOX_ID + 2 * line_index as u64;
+
+        // Room at the start comes before an initial letter at the same index
+        if start > 0.0 {
+            room_boxes.push(InlineItemBuilder::Room { id, index: range.start, width: start });
+            advance += start;
+        }
+        // Room at the end goes before the trailing whitespace, the line can't break between them
+        if end > 0.0 {
+            let content_end = text[range.clone()].trim_end().len() + range.start;
+            items.push(InlineItemBuilder::Room { id: id + 1, index: content_end, width: end });
+            advance += end;
+        }
+
+        if let Some(wrap_width) = wrap_width {
+            line_indents.push((wrap_width - advance - 0.01).max(0.0));
+        }
+    }
+
+    items.splice(0..0, room_boxes);
+
+    let mut layout = build_layout(tree, text, items, spans, 1.0);
+    breaking.line_indents = line_indents;
+    break_layout(&mut layout, text, breaking);
+    layout
+}
+
+/// Font sizes for `fontSize: fit` are searched in quarter pixels,
+/// so measurements of the same content reuse the cached layouts
+const FIT_STEPS_PER_PIXEL: f32 = 4.0;
+
+/// Search for the largest font size the inline content fits its box at.
+/// Returns the scale of the content's own font size and the broken layout at that scale.
+///
+/// Without a `maxFontSize` the size is bound by the box height, or
+/// its width when the height is not known. `break_lines` breaks a layout and
+/// returns the room each line keeps for floats, lines fit beside their room
+fn fit_font_size(
+    tree: &mut LayoutTree,
+    node_id: NodeId,
+    text: &str,
+    items: &[InlineItemBuilder],
+    spans: &[(Range<usize>, StyleContext)],
+    break_lines: impl Fn(&mut parley::Layout<[u8; 4]>) -> Vec<f32>,
+    box_size: Size<Option<f32>>,
+) -> (f32, parley::Layout<[u8; 4]>) {
+    let style = tree.node_from_id(node_id).style_context;
+    let base = style.resolved_font_size().max(f32::EPSILON);
+    let rem = style.resolved_root_font_size();
+    let dpi = style.dpi();
+
+    let min = match style.min_font_size() {
+        Dimension::Auto => 6.0,
+        size => size.as_pixels(base, rem, base, dpi),
+    };
+
+    let max = match (style.max_font_size(), box_size.height.or(box_size.width)) {
+        (Dimension::Auto, Some(bound)) if bound.is_finite() => bound,
+        (Dimension::Auto, _) => base,
+        (size, _) => size.as_pixels(base, rem, base, dpi),
+    };
+
+    let mut low = (min * FIT_STEPS_PER_PIXEL).round().max(1.0) as u32;
+    let mut high = ((max * FIT_STEPS_PER_PIXEL).round() as u32).max(low);
+
+    // Layouts are shaped once per size, line breaking depends on the box.
+    // Inline boxes are shaped in, their sizes change with the container width
+    let boxes: Vec<f32> = items.iter()
+        .flat_map(|item| match *item {
+            InlineItemBuilder::Inline { width, height, .. } => vec![width, height],
+            InlineItemBuilder::Room { width, .. } => vec![width],
+            _ => vec![],
+        })
+        .collect();
+
+    let node = tree.node_from_id_mut(node_id);
+    if node.inline_fit_boxes != boxes {
+        node.inline_fit_cache.clear();
+        node.inline_fit_boxes = boxes;
+    }
+    let mut cache = std::mem::take(&mut node.inline_fit_cache);
+
+    let mut layout_at = |tree: &mut LayoutTree, step: u32| -> (parley::Layout<[u8; 4]>, Vec<f32>) {
+        let mut layout = match cache.iter().find(|(cached, _)| *cached == step) {
+            Some((_, layout)) => layout.clone(),
+            None => {
+                let layout = build_layout(tree, text, items, spans, step as f32 / FIT_STEPS_PER_PIXEL / base);
+                cache.push((step, layout.clone()));
+                layout
+            }
+        };
+
+        let room = break_lines(&mut layout);
+        (layout, room)
+    };
+
+    let fits = |(layout, room): &(parley::Layout<[u8; 4]>, Vec<f32>)| -> bool {
+        let fits_width = box_size.width.is_none_or(|width| {
+            layout.lines().enumerate().all(|(index, line)| {
+                let room = room.get(index).copied().unwrap_or(0.0);
+                line.metrics().advance - line.metrics().trailing_whitespace + room <= width + 0.01
+            })
+        });
+        let fits_height = box_size.height.is_none_or(|height| layout.height() <= height + 0.01);
+
+        fits_width && fits_height
+    };
+
+    // The smallest size is used when nothing fits
+    let mut best = None;
+
+    while low < high {
+        let step = (low + high).div_ceil(2);
+        let layout = layout_at(tree, step);
+
+        if fits(&layout) {
+            low = step;
+            best = Some(layout);
+        } else {
+            high = step - 1;
+        }
+    }
+
+    let (layout, _) = best.unwrap_or_else(|| layout_at(tree, low));
+    tree.node_from_id_mut(node_id).inline_fit_cache = cache;
+
+    (low as f32 / FIT_STEPS_PER_PIXEL / base, layout)
+}
+
 /// Layout an inline container.
-/// We use the tree to compute inline blocks
+/// We use the tree to compute inline blocks. Inline-block boxes keep
+/// their width, height and margins, they are sized on their own here
 pub(super) fn compute_inline_layout (tree: &mut LayoutTree, node_id: NodeId, inputs: LayoutInput) -> LayoutOutput {
     let mut i_text = String::new();
     let mut i_items: Vec<InlineItemBuilder> = Vec::new();
 
+    // Styles for each text range, used when collecting fragments
+    let mut inline_spans: Vec<(Range<usize>, StyleContext)> = Vec::new();
+
+    // Inline-block boxes with their border box size and margins.
+    // Used to position the boxes once parley has placed them
+    let mut inline_blocks: Vec<(NodeId, Size<f32>, Rect<f32>)> = Vec::new();
+
+    // Floats in the order they came in
+    let mut floats: Vec<FloatBox> = Vec::new();
+
+    // Percentages on inline-block boxes resolve against the inline container
+    let container_width = inputs.known_dimensions.width.or(inputs.available_space.width.into_option());
+
     let node = tree.node_from_id(node_id);
     let ws = node.style_context.white_space();
     let transform = node.style_context.text_transform();
+    let tab_size = node.style_context.tab_size().round() as usize;
+    let tab_stops = node.style_context.tab_stops();
+    let mut tab_count = 0;
+    let alignment = to_parley_alignment(node.style_context.text_align());
+    let node_style = node.style_context;
+    let fit = node.style_context.font_size_fit();
+
     let mut trailing_ws = false;
 
+    match node.style_context.direction() {
+        Direction::Ltr => i_text.push(LTR_MARK),
+        Direction::Rtl => i_text.push(RTL_MARK),
+        Direction::Auto => {}
+    }
+
     for child_id in tree.node_from_id(node_id).children.clone() {
         let child_node = tree.node_from_id(child_id);
         match child_node.kind {
             LayoutNodeKind::Text => {
                 if let Some(text) = &child_node.text {
-                    let (transformed, has_trailing_ws) = transform_with_ws(text, ws, transform, !trailing_ws);
+                    // With tab stops tabs are kept, even where whitespace collapses
+                    let keep_tabs = !tab_stops.is_empty() && matches!(ws, WhiteSpace::Normal | WhiteSpace::NoWrap | WhiteSpace::PreLine);
+                    let (mut transformed, has_trailing_ws) = if keep_tabs && text.contains('\t') {
+                        transform_keeping_tabs(text, ws, transform, !trailing_ws)
+                    } else {
+                        transform_with_ws(text, ws, transform, !trailing_ws)
+                    };
                     if transformed.is_empty() { continue; }
                     trailing_ws = has_trailing_ws;
 
-                    let start = i_text.len();
-                    i_text.push_str(&transformed);
+                    // Preformatted text keeps its punctuation as written
+                    if child_node.style_context.smart_punctuation() && !matches!(ws, WhiteSpace::Pre | WhiteSpace::PreWrap) {
+                        let previous = i_text.chars().next_back().filter(|c| !matches!(c, &LTR_MARK | &RTL_MARK));
+                        transformed = smart_punctuation(&transformed, previous);
+                    }
+
+                    // Preserved tabs advance to the next tab column of the current line
+                    if matches!(ws, WhiteSpace::Pre | WhiteSpace::PreWrap) && tab_stops.is_empty() && transformed.contains('\t') {
+                        let column = i_text.rsplit('\n').next()
+                            .map(|line| line.chars().filter(|c| !matches!(c, &LTR_MARK | &RTL_MARK)).count())
+                            .unwrap_or(0);
+                        transformed = expand_tabs(&transformed, tab_size, column);
+                    }
+
+                    // Soft hyphens are break opportunities, shaping drops them
+                    // so they are only visible when a line breaks there
+                    match child_node.style_context.hyphens() {
+                        Hyphens::None => transformed.retain(|c| c != SOFT_HYPHEN),
+                        Hyphens::Manual => {}
+                        Hyphens::Auto => {
+                            if let Some(lang) = hypher::Lang::from_iso(child_node.style_context.lang())
+                                && !matches!(ws, WhiteSpace::NoWrap | WhiteSpace::Pre)
+                            {
+                                transformed = hyphenate(&transformed, lang);
+                            }
+                        }
+                    }
+
+                    // The first span also styles the direction mark
+                    let start = if i_items.is_empty() { 0 } else { i_text.len() };
+
+                    // Tabs left in the text advance to the tab stops, they are laid out as boxes
+                    for (i, piece) in transformed.split('\t').enumerate() {
+                        if i > 0 {
+                            i_items.push(InlineItemBuilder::Tab { id: TAB_BOX_ID + tab_count, index: i_text.len() });
+                            tab_count += 1;
+                        }
+                        i_text.push_str(piece);
+                    }
+
                     let end = i_text.len();
-                    i_items.push(InlineItemBuilder::Text { range: start..end, styles: parley_style(&child_node.style_context) })
+                    i_items.push(InlineItemBuilder::Text { span: inline_spans.len() });
+                    inline_spans.push((start..end, child_node.style_context));
+                }
+            }
+            // Floats are laid out on their own and placed once the lines are broken
+            LayoutNodeKind::Container if child_node.style_context.float() != Float::None => {
+                let side = child_node.style_context.float();
+                let clear = child_node.style_context.clear();
+                let margin = inline_block_margin(&child_node.style_context, container_width.unwrap_or(0.0));
+
+                let available_width = match container_width {
+                    Some(width) => AvailableSpace::Definite((width - margin.left - margin.right).max(0.0)),
+                    None => inputs.available_space.width,
+                };
+
+                let layout = tree.compute_child_layout(child_id, LayoutInput {
+                    run_mode: RunMode::PerformLayout,
+                    sizing_mode: SizingMode::InherentSize,
+                    axis: RequestedAxis::Both,
+                    known_dimensions: Size::NONE,
+                    parent_size: Size { width: container_width, height: None },
+                    available_space: Size { width: available_width, height: AvailableSpace::MaxContent },
+                    vertical_margins_are_collapsible: Line::FALSE,
+                });
+
+                // Whitespace after floats at the start of the content collapses away, as after a space
+                if i_items.iter().all(|item| matches!(item, InlineItemBuilder::Float { .. })) {
+                    trailing_ws = true;
                 }
+
+                i_items.push(InlineItemBuilder::Float { id: child_id, index: i_text.len() });
+                floats.push(FloatBox { id: child_id, side, clear, index: 0, size: layout.size, margin, x: 0.0, y: 0.0 });
             }
             // Containers directly in an inline layout are always treated as inline block
             LayoutNodeKind::Container => {
                 let inline_index = i_text.len();
+                let margin = inline_block_margin(&child_node.style_context, container_width.unwrap_or(0.0));
+
+                // Inline-blocks are sized on their own, never with the known dimensions
+                // of the inline container. This lets width, height, min and max sizes apply
+                // and shrink-wraps the box when they are not set
+                let available_width = match container_width {
+                    Some(width) => AvailableSpace::Definite((width - margin.left - margin.right).max(0.0)),
+                    None => inputs.available_space.width,
+                };
+
+                let layout = tree.compute_child_layout(child_id, LayoutInput {
+                    run_mode: RunMode::PerformLayout,
+                    sizing_mode: SizingMode::InherentSize,
+                    axis: RequestedAxis::Both,
+                    known_dimensions: Size::NONE,
+                    parent_size: Size { width: container_width, height: None },
+                    available_space: Size { width: available_width, height: AvailableSpace::MaxContent },
+                    vertical_margins_are_collapsible: Line::FALSE,
+                });
 
-                let layout = tree.compute_child_layout(child_id, inputs);
-                let width = layout.content_size.width;
-                let height = layout.content_size.height;
+                // The inline box reserves the margin box
+                let width = layout.size.width + margin.left + margin.right;
+                let height = layout.size.height + margin.top + margin.bottom;
 
                 i_items.push(InlineItemBuilder::Inline { id: child_id, index: inline_index, width, height });
+                inline_blocks.push((child_id, layout.size, margin));
 
                 // inline block elements reset the trailing_ws to allow for opening whitespace
                 trailing_ws = false;
@@ -148,33 +1406,132 @@ pub(super) fn compute_inline_layout (tree: &mut LayoutTree, node_id: NodeId, inp
         }
     }
 
-    let mut builder = tree.context.parley_layout_context
-        .ranged_builder(&mut tree.context.parley_font_context, &i_text, tree.context.parley_display_scale, true);
+    let available_width = Some(inputs.available_space.width.unwrap_or(f32::INFINITY));
 
-    for i_item in i_items {
-        match i_item {
-            InlineItemBuilder::Inline { id, index, width, height } => {
-                builder.push_inline_box(InlineBox{
-                    id: id.into(), width, height, index
-                });
-            },
-            InlineItemBuilder::Text { range, styles } => {
-                for s in styles {
-                    builder.push(s, range.clone());
+    // nowrap and pre never soft wrap, lines only break at preserved newlines
+    let wrap_width = match ws {
+        WhiteSpace::NoWrap | WhiteSpace::Pre => None,
+        WhiteSpace::Normal | WhiteSpace::PreWrap | WhiteSpace::PreLine => available_width,
+    };
+
+    let em = node_style.resolved_font_size();
+    let rem = node_style.resolved_root_font_size();
+    let dpi = node_style.dpi();
+
+    let mut resolved_tab_stops: Vec<(f32, TabAlign)> = tab_stops.iter()
+        .map(|(position, align)| (position.as_pixels(container_width.unwrap_or(0.0), rem, em, dpi), *align))
+        .collect();
+    resolved_tab_stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut breaking = LineBreaking { wrap_width, tab_stops: resolved_tab_stops, tab_size: tab_size as f32, line_indents: vec![], container_width: container_width.unwrap_or(0.0) };
+
+    synthesize_font_variants(tree, &mut i_text, &mut i_items, &mut inline_spans);
+
+    // Fitted text keeps its first letter, the letter is sized from the text
+    let initial_letter = match node_style.initial_letter() {
+        0 => None,
+        _ if fit => None,
+        lines => take_initial_letter(tree, lines, &mut i_text, &mut i_items, &mut inline_spans),
+    }.map(|(letter, letter_box)| {
+        i_items.push(letter_box);
+        letter
+    });
+
+    // Floats are placed from where they are in the text, after the initial letter moved it
+    for item in &i_items {
+        if let InlineItemBuilder::Float { id, index } = item
+            && let Some(float) = floats.iter_mut().find(|float| float.id == *id)
+        {
+            float.index = *index;
+        }
+    }
+
+    // Floats of shrink-wrapped content have no width to keep room in until the text is fitted
+    let fit_floats = fit && container_width.is_some() && !floats.is_empty();
+
+    let (scale, mut layout) = if fit {
+        let box_size = Size {
+            width: container_width,
+            height: inputs.known_dimensions.height.or(inputs.available_space.height.into_option()),
+        };
+
+        // Each size places the floats afresh, as they only move down within one placement
+        let break_lines = |layout: &mut parley::Layout<[u8; 4]>| {
+            break_layout(layout, &i_text, &breaking);
+            if !fit_floats { return vec![] }
+
+            let mut breaking = breaking.clone();
+            settle_line_room(layout, &i_text, &mut breaking, None, &mut floats.clone());
+            breaking.line_indents
+        };
+        fit_font_size(tree, node_id, &i_text, &i_items, &inline_spans, break_lines, box_size)
+    } else {
+        let layout = break_lines_with_room(tree, &i_text, &mut i_items, &inline_spans, &mut breaking, initial_letter.as_ref(), &mut floats);
+        (1.0, layout)
+    };
+
+    if scale != 1.0 {
+        for (_, style) in inline_spans.iter_mut() {
+            style.set_resolved_font_size(style.resolved_font_size() * scale);
+        }
+    }
+
+    // Fitted text is broken around its floats again at the size it fits at, with the room kept as boxes
+    if fit_floats {
+        layout = break_lines_with_room(tree, &i_text, &mut i_items, &inline_spans, &mut breaking, None, &mut floats);
+    }
+
+    // Lines align within the width of the inline content, when it is known
+    layout.align(container_width, alignment, AlignmentOptions::default());
+
+    // Inline-block boxes are positioned by parley, so we record their
+    // layout here. The border box sits inside the margins of the inline box.
+    for line in layout.lines() {
+        for item in line.items() {
+            if let PositionedLayoutItem::InlineBox(inline_box) = item {
+                let box_id = NodeId::from(inline_box.id);
+
+                if let Some((_, size, margin)) = inline_blocks.iter().find(|(id, ..)| *id == box_id) {
+                    tree.set_unrounded_layout(box_id, &Layout {
+                        location: Point { x: inline_box.x + margin.left, y: inline_box.y + margin.top },
+                        size: *size,
+                        margin: *margin,
+                        ..Layout::new()
+                    });
                 }
             }
         }
     }
 
-    let mut layout = builder.build(&i_text);
+    // Floats of shrink-wrapped content are placed against the width of its lines
+    if container_width.is_none() {
+        place_floats(&mut floats, Some(&layout), container_width.unwrap_or(layout.width()));
+    }
 
-    let available_width = Some(inputs.available_space.width.unwrap_or(f32::INFINITY));
+    for float in &floats {
+        tree.set_unrounded_layout(float.id, &Layout {
+            location: Point { x: float.x + float.margin.left, y: float.y + float.margin.top },
+            size: float.size,
+            margin: float.margin,
+            ..Layout::new()
+        });
+    }
+
+    let truncation = truncate_lines(&layout, &tree.node_from_id(node_id).style_context, container_width);
 
-    layout.break_all_lines(available_width);
-    layout.align(available_width, Alignment::Start, AlignmentOptions::default());
+    // Decimal content keeps the width it lines up within, so lines can shift to the far edge
+    let total_width = match (node_style.text_align(), inputs.known_dimensions.width) {
+        (TextAlign::Decimal(_), Some(width)) => width,
+        _ => layout.width(),
+    };
 
-    let total_width = layout.width();
-    let total_height = layout.height();
+    // Hidden lines take no space, an initial letter or floats taller than the text do
+    let total_height = truncation
+        .and_then(|truncation| layout.get(truncation.lines - 1))
+        .map(|line| line.metrics().max_coord)
+        .unwrap_or(layout.height())
+        .max(initial_letter.as_ref().map_or(0.0, |letter| letter.bottom(&layout)))
+        .max(floats.iter().map(FloatBox::bottom).fold(0.0, f32::max));
 
     let baseline_y = layout.lines()
         .next()
@@ -185,6 +1542,10 @@ pub(super) fn compute_inline_layout (tree: &mut LayoutTree, node_id: NodeId, inp
 
     let node_mut = tree.node_from_id_mut(node_id);
     node_mut.inline_layout = Some(layout);
+    node_mut.inline_text = i_text;
+    node_mut.inline_spans = inline_spans;
+    node_mut.inline_truncation = truncation;
+    node_mut.inline_initial_letter = initial_letter;
 
     let size = Size { width: total_width, height: total_height };
     let content_size = size;
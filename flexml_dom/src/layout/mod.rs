@@ -1,19 +1,39 @@
 mod tree;
 mod inline;
+mod columns;
+mod paginate;
+mod running;
+mod sections;
+mod print;
 mod taffy_style;
 pub mod fragments;
 
+#[cfg(test)]
+mod tests;
+
 use crate::document::nodes::Node;
 use crate::document::parser::FlexmlDocument;
-use crate::layout::fragments::{collect_fragments, Fragment, FragmentGroup, Radius, Rect};
+use crate::layout::columns::is_multi_column;
+use crate::layout::fragments::{collect_fragments, style_fragments, FragmentGroup, Rect};
+use crate::layout::paginate::{collect_root_break_points, fill_column_pages, page_ranges, paginate_fragments};
+use crate::layout::print::print_page;
+use crate::layout::running::{collect_page_region_fragments, page_token_node, PageToken, PageValues};
+use crate::layout::sections::{page_selectors, split_sections, PageSetup, Section};
 use crate::layout::tree::{LayoutNode, LayoutNodeKind, LayoutTree};
-use crate::styles::context::{Display, StyleContext};
+use crate::styles::context::{BreakBetween, Clear, Display, Float, PageRegion, StyleContext, WhiteSpace};
 use crate::styles::style::AtomicStyle;
 use crate::styles::style_registry::StyleRegistry;
 use parley::{FontContext, LayoutContext};
-use taffy::{AvailableSpace, NodeId, Size};
+use std::path::PathBuf;
+use taffy::{AvailableSpace, NodeId, Point, Size};
 
+/// A rendered page. Its size includes the bleed and the print marks
+/// around the trim box, the trimmed page size
 pub struct FlexmlPage {
+    pub width: f32,
+    pub height: f32,
+    pub trim_box: Rect,
+    pub bleed_box: Rect,
     pub fragments: Vec<FragmentGroup>,
 }
 
@@ -24,6 +44,8 @@ pub struct FlexmlLayout {
     pub page_height: f32,
     pub dpi: f32,
     pub pages: Vec<FlexmlPage>,
+    /// Files of the document by path id, image fragments refer to them
+    pub paths: Vec<PathBuf>,
     pub context: FlexmlLayoutContext,
 }
 
@@ -45,16 +67,7 @@ impl Default for FlexmlLayoutContext {
 
 impl FlexmlLayout {
     pub fn new(doc: &FlexmlDocument, layout_context: FlexmlLayoutContext) -> FlexmlLayout {
-        let dpi = doc.root_style.dpi();
-        let none = 0.0f32;
-        let page_width = doc.root_style.width().as_pixels(none, none, none, dpi);
-        let page_height = doc.root_style.height().as_pixels(none, none, none, dpi);
-
-        let mut pages = vec!(FlexmlPage{
-            fragments: vec![],
-        });
-
-        let current_page = pages.last_mut().unwrap();
+        let document_setup = PageSetup::new(&doc.root_style);
 
         // TODO link up fonts from doc.style_registry to the parley fonts
         //layout_context.parley_font_context.collection.register_fonts()
@@ -62,53 +75,167 @@ impl FlexmlLayout {
         // This holds references to all layout nodes that are generated
         let mut layout_tree = LayoutTree::new(layout_context);
 
-        //Render page style fragments
-        let page_rect = Rect::new(0.0,0.0,page_width, page_height);
-        let mut page_fragments = FragmentGroup::new(page_rect);
-        page_fragments.fragments.push(Fragment::bg(page_rect, Radius::zero(), doc.root_style.bg_color()));
-        current_page.fragments.push(page_fragments);
-
-
-        // TODO, available space should include padding calculations
-        let em = doc.root_style.resolved_font_size();
-        let rem = doc.root_style.resolved_root_font_size();
-        let dpi = doc.root_style.dpi();
-
-        let page_top = doc.root_style.padding_top().as_pixels(page_width, rem, em, dpi);
-        let page_left = doc.root_style.padding_left().as_pixels(page_width, rem, em, dpi);
-        let page_bottom = doc.root_style.padding_bottom().as_pixels(page_width, rem, em, dpi);
-        let page_right = doc.root_style.padding_right().as_pixels(page_width, rem, em, dpi);
-        let page_space: Size<AvailableSpace> = Size{width: AvailableSpace::from(page_width - (page_left + page_right)), height: AvailableSpace::MaxContent};
+        // Each section is laid out and paginated with its own page setup,
+        // and each page of it gets the page selector styles of its page number.
+        // Headers and footers outside of sections are used by every section
+        let mut sections: Vec<Section> = vec![];
+        let mut document_regions = vec![];
+        let mut page_number = 0;
+
+        for (section_styles, nodes) in split_sections(doc) {
+            let styles = section_styles.unwrap_or(&[]);
+            let first_page = page_number + 1;
+            let setup = PageSetup::new(&doc.style_registry.resolve_page_style(&[], styles));
+            let page_setup = |index: usize| PageSetup::new(&doc.style_registry.resolve_page_style(&page_selectors(first_page + index), styles))
+                .with_content_width(setup.content_width());
+
+            let first_region = layout_tree.page_regions.len();
+            let pages = layout_section(&mut layout_tree, &doc.style_registry, &setup, page_setup, nodes);
+            let regions = layout_tree.page_regions.split_off(first_region);
+
+            let section = if section_styles.is_none() {
+                document_regions.extend(regions);
+
+                // Content around sections that holds no text or boxes makes no pages
+                let Some(pages) = pages else { continue };
+                Section { regions: vec![], pages }
+            } else {
+                let pages = pages.unwrap_or_else(|| vec![(page_setup(0), vec![])]);
+                Section { regions, pages }
+            };
+
+            page_number += section.pages.len();
+            sections.push(section);
+        }
 
-        //We treat the top level as a box container with its children
-        //being root nodes. Each root node is laid out and fragmented/paginated
-        //one by one
-        let root_layout_id = cascade_container(&mut layout_tree, true, &doc.style_registry, &doc.root_style, &doc.nodes, &[]);
-        let root_layout = layout_tree.node_from_id(root_layout_id);
-        let root_node_ids = root_layout.children.clone();
+        if sections.is_empty() {
+            let setup = PageSetup::new(&doc.style_registry.resolve_page_style(&page_selectors(1), &[]));
+            sections.push(Section { regions: vec![], pages: vec![(setup, vec![])] });
+        }
 
-        let _current_page_max_y = page_height - page_bottom;
-        let mut current_page_y = page_top;
-        let current_page_x = page_left;
+        let page_count = sections.iter().map(|section| section.pages.len()).sum();
+        let mut pages = Vec::with_capacity(page_count);
+
+        for section in sections {
+            // The last region of each kind declared is used, section ones first
+            let region = |region: PageRegion| section.regions.iter().rev()
+                .chain(document_regions.iter().rev())
+                .find(|(r, _)| *r == region)
+                .map(|(_, id)| *id);
+            let header = region(PageRegion::Header);
+            let footer = region(PageRegion::Footer);
+            let overlay = region(PageRegion::Overlay);
+            let underlay = region(PageRegion::Underlay);
+
+            for (setup, content) in section.pages {
+                // Overlays and underlays are placed against the whole page, ignoring its padding
+                let page_rect = Rect::new(0.0, 0.0, setup.width, setup.height);
+                let regions = [
+                    (header.filter(|_| setup.style.page_header()), Rect::new(setup.left, 0.0, setup.content_width(), setup.top)),
+                    (footer.filter(|_| setup.style.page_footer()), Rect::new(setup.left, setup.height - setup.bottom, setup.content_width(), setup.bottom)),
+                    (overlay, page_rect),
+                ];
+
+                // Every page gets the background, image and border of its root style
+                let mut page_fragments = FragmentGroup::new(page_rect);
+                page_fragments.fragments = style_fragments(&setup.style, page_rect, true);
+
+                let values = PageValues { page: pages.len() + 1, pages: page_count, title: &doc.title };
+                let mut fragments = vec![page_fragments];
+
+                if let Some(node_id) = underlay {
+                    collect_page_region_fragments(&mut layout_tree, node_id, &values, page_rect, &mut fragments);
+                }
 
-        // loop through root_layout_nodes and generate fragments + paginate
-        for root_layout_node in root_node_ids {
-            layout_tree.compute_layout(root_layout_node, page_space,true);
+                fragments.extend(content);
 
-            layout_tree.print_tree(root_layout_node);
-            collect_fragments(&layout_tree, root_layout_node, current_page_x, current_page_y, &mut current_page.fragments);
+                for (node_id, band) in regions {
+                    if let Some(node_id) = node_id {
+                        collect_page_region_fragments(&mut layout_tree, node_id, &values, band, &mut fragments);
+                    }
+                }
 
-            let root_node = layout_tree.node_from_id(root_layout_node);
-            current_page_y += root_node.final_layout.size.height;
+                pages.push(print_page(&setup, fragments));
+            }
         }
 
-        FlexmlLayout{ page_width, page_height, dpi,
+        // Roll pages take the height of their content, the first page stands for them
+        let page_height = if document_setup.roll {
+            pages.first().map_or(document_setup.height, |page: &FlexmlPage| page.trim_box.height)
+        } else {
+            document_setup.height
+        };
+
+        FlexmlLayout{
+            page_width: document_setup.width,
+            page_height,
+            dpi: doc.root_style.dpi(),
             pages,
+            paths: doc.style_registry.paths().to_vec(),
             context: layout_tree.context
         }
     }
 }
 
+/// Lay out the top level nodes of a section and split them into pages.
+/// The content is laid out at the width of the section setup, then each page
+/// takes the height and margins of its own setup. Returns None when the nodes hold no content
+fn layout_section(
+    tree: &mut LayoutTree,
+    style_registry: &StyleRegistry,
+    setup: &PageSetup,
+    page_setup: impl Fn(usize) -> PageSetup,
+    nodes: &[Node],
+) -> Option<Vec<(PageSetup, Vec<FragmentGroup>)>> {
+    let page_space: Size<AvailableSpace> = Size{width: AvailableSpace::from(setup.content_width()), height: AvailableSpace::MaxContent};
+
+    //We treat the top level as a box container with its children
+    //being root nodes. Each root node is laid out and fragmented/paginated
+    //one by one
+    let root_layout_id = cascade_container(tree, true, style_registry, &setup.style, nodes, &[]);
+    let root_node_ids = tree.node_from_id(root_layout_id).children.clone();
+    if root_node_ids.is_empty() { return None }
+
+    // Root nodes are laid out one after another in a single flow,
+    // which is then split into pages at the best break points
+    let mut flow_fragments = vec![];
+    let mut break_points = vec![];
+    let mut flow_y = 0.0f32;
+    let mut previous_root = None;
+
+    for root_layout_node in root_node_ids {
+        tree.compute_layout(root_layout_node, page_space,true);
+
+        // Columns are filled to the room on each page, known once the content above them is laid out
+        let mut root_breaks = break_points.clone();
+        collect_root_break_points(tree, previous_root, root_layout_node, flow_y, &mut root_breaks);
+        if fill_column_pages(tree, root_layout_node, flow_y, &root_breaks, &|index| page_setup(index).content_height()) {
+            tree.compute_layout(root_layout_node, page_space,true);
+        }
+
+        tree.print_tree(root_layout_node);
+        collect_root_break_points(tree, previous_root, root_layout_node, flow_y, &mut break_points);
+        collect_fragments(tree, root_layout_node, 0.0, flow_y, &mut flow_fragments);
+
+        let root_node = tree.node_from_id(root_layout_node);
+        flow_y += root_node.final_layout.size.height;
+        previous_root = Some(root_layout_node);
+    }
+
+    let page_flow_ranges = page_ranges(&break_points, flow_y, |index| page_setup(index).content_height());
+    let setups: Vec<PageSetup> = page_flow_ranges.iter().enumerate()
+        .map(|(index, range)| {
+            let mut setup = page_setup(index);
+            setup.fit_content(range.end - range.start);
+            setup
+        })
+        .collect();
+    let origins: Vec<Point<f32>> = setups.iter().map(|setup| Point { x: setup.left, y: setup.top }).collect();
+
+    let pages = paginate_fragments(flow_fragments, &page_flow_ranges, &origins);
+    Some(setups.into_iter().zip(pages).collect())
+}
+
 /// Core cascade recurse
 ///
 /// Cascades styles and collects LayoutNodes
@@ -132,12 +259,17 @@ fn cascade_container(
     box_children: &[Node],
     box_styles: &[AtomicStyle],
 ) -> NodeId {
-    let layout_style = if is_root {
+    let mut layout_style = if is_root {
         *parent_style
     } else {
         style_registry.resolve_style(parent_style, box_styles)
     };
 
+    // Floats, headers and footers are laid out as blocks
+    if (layout_style.float() != Float::None || layout_style.page_region() != PageRegion::None) && matches!(layout_style.display(), Display::Inline | Display::InlineBlock) {
+        layout_style.set_display(Display::Block);
+    }
+
     let mut layout_children: Vec<NodeId> = Vec::new();
     let mut inline_buffer: Vec<NodeId> = Vec::new();
 
@@ -147,6 +279,27 @@ fn cascade_container(
             Node::BoxContainer { styles, children } => {
                 let child_style = style_registry.resolve_style(&layout_style, styles);
 
+                // Headers and footers are taken out of the flow and laid out on every page
+                let region = child_style.page_region();
+                if region != PageRegion::None {
+                    let child_node = cascade_container(
+                        tree, false, style_registry, &child_style, children, styles,
+                    );
+
+                    tree.page_regions.push((region, child_node));
+                    continue;
+                }
+
+                // Floats are taken out of the flow and laid out with the inline content around them
+                if child_style.float() != Float::None {
+                    let child_node = cascade_container(
+                        tree, false, style_registry, &child_style, children, styles,
+                    );
+
+                    inline_buffer.push(child_node);
+                    continue;
+                }
+
                 match child_style.display() {
                     // Inline nodes are flattened into the inline buffer
                     Display::Inline => {
@@ -168,6 +321,7 @@ fn cascade_container(
                         );
 
                         // flush entire inline buffer before adding block child
+                        carry_floats(tree, &mut inline_buffer, child_node);
                         flush_inline_buffer(tree, &layout_style, &mut inline_buffer, &mut layout_children);
                         layout_children.push(child_node);
                     }
@@ -177,6 +331,22 @@ fn cascade_container(
                 let text_node = tree.add_node(LayoutNode::new_text(layout_style, text.to_string()));
                 inline_buffer.push(text_node);
             }
+            Node::Raw(text) => {
+                let text_node = tree.add_node(LayoutNode::new_text(raw_style(&layout_style), text.to_string()));
+                inline_buffer.push(text_node);
+            }
+            // A page break is an empty block that starts a new page
+            Node::Tag { name } if name.eq_ignore_ascii_case("pagebreak") => {
+                let mut break_style = style_registry.resolve_style(&layout_style, &[]);
+                break_style.set_display(Display::Block);
+                break_style.set_break_before(BreakBetween::Page);
+
+                flush_inline_buffer(tree, &layout_style, &mut inline_buffer, &mut layout_children);
+                layout_children.push(tree.add_node(LayoutNode::new_container(LayoutNodeKind::Container, break_style, vec![])));
+            }
+            Node::Tag { name } if let Some(token) = PageToken::from_tag(name) => {
+                inline_buffer.push(tree.add_node(page_token_node(layout_style, token)));
+            }
             _ => {
                 flush_inline_buffer(tree, &layout_style, &mut inline_buffer, &mut layout_children);
             }
@@ -186,6 +356,13 @@ fn cascade_container(
     // flush inline buffer once at the very end
     flush_inline_buffer(tree, &layout_style, &mut inline_buffer, &mut layout_children);
 
+    // Multi-column content flows through a columns node, the container keeps the box styles
+    if is_multi_column(&layout_style) {
+        let mut columns = LayoutNode::new_container(LayoutNodeKind::Columns, layout_style, layout_children);
+        columns.style = taffy::Style::default();
+        layout_children = vec![tree.add_node(columns)];
+    }
+
     // create the container node (Block or Flex)
     tree.add_node(LayoutNode::new_container(
         LayoutNodeKind::Container,
@@ -194,6 +371,39 @@ fn cascade_container(
     ))
 }
 
+/// Floats right before a block wrap the first text of the block, unless it clears them.
+/// Only a buffer holding nothing but floats and whitespace is carried
+fn carry_floats(tree: &mut LayoutTree, buffer: &mut Vec<NodeId>, block: NodeId) {
+    let is_float = |tree: &LayoutTree, id: NodeId| tree.node_from_id(id).style_context.float() != Float::None;
+    let is_whitespace = |tree: &LayoutTree, id: NodeId| tree.node_from_id(id).text.as_ref().is_some_and(|text| text.trim().is_empty());
+
+    if !buffer.iter().all(|&id| is_float(tree, id) || is_whitespace(tree, id)) { return }
+
+    let block_node = tree.node_from_id(block);
+    let clear = block_node.style_context.clear();
+    let Some(&content) = block_node.children.first() else { return };
+    if !matches!(tree.node_from_id(content).kind, LayoutNodeKind::InlineContent) { return }
+
+    let carried: Vec<NodeId> = buffer.iter().copied()
+        .filter(|&id| is_float(tree, id) && !clears(clear, tree.node_from_id(id).style_context.float()))
+        .collect();
+    if carried.is_empty() { return }
+
+    buffer.retain(|&id| is_float(tree, id) && !carried.contains(&id));
+    tree.node_from_id_mut(content).children.splice(0..0, carried);
+}
+
+fn clears(clear: Clear, float: Float) -> bool {
+    matches!((clear, float), (Clear::Both, _) | (Clear::Left, Float::Left) | (Clear::Right, Float::Right))
+}
+
+/// Raw text is laid out as written, without smart punctuation
+fn raw_style(style: &StyleContext) -> StyleContext {
+    let mut raw = *style;
+    raw.set_smart_punctuation(false);
+    raw
+}
+
 /// If a buffer has nodes, wraps them in an InlineContent LayoutNode
 /// This is used to clean out a buffer and push the generated LayoutNode
 /// into the completed output vec
@@ -203,10 +413,27 @@ fn flush_inline_buffer(
     buffer: &mut Vec<NodeId>,
     output: &mut Vec<NodeId>,
 ) {
+    // Whitespace between blocks collapses away. Page tokens are only filled in later
+    let collapses = |id: &NodeId| {
+        let node = tree.node_from_id(*id);
+        node.page_token.is_none()
+            && node.text.as_ref().is_some_and(|text| text.trim().is_empty())
+            && matches!(node.style_context.white_space(), WhiteSpace::Normal | WhiteSpace::NoWrap)
+    };
+    if buffer.iter().all(collapses) {
+        buffer.clear();
+    }
+
     if !buffer.is_empty() {
+        // Only the first inline content of a block starts with its initial letter
+        let mut style = *style;
+        if !output.is_empty() {
+            style.set_initial_letter(0);
+        }
+
         let inline_content = tree.add_node(LayoutNode::new_container(
             LayoutNodeKind::InlineContent,
-            *style,
+            style,
             std::mem::take(buffer),
         ));
         output.push(inline_content);
@@ -232,10 +459,22 @@ fn flush_inline_to_buffer(
             Node::Text(text) | Node::Whitespace(text) => {
                 output.push(tree.add_node(LayoutNode::new_text(*inherited_style, text.to_string())));
             }
+            Node::Raw(text) => {
+                output.push(tree.add_node(LayoutNode::new_text(raw_style(inherited_style), text.to_string())));
+            }
             Node::BoxContainer { styles, children } => {
-                let container_style = style_registry.resolve_style(inherited_style, styles);
+                let mut container_style = style_registry.resolve_style(inherited_style, styles);
+
+                // Spans nested in super or subscript text stay raised or lowered with it
+                if !container_style.has_vertical_align() && inherited_style.has_vertical_align() {
+                    container_style.set_vertical_align(inherited_style.vertical_align());
+                }
+
                 flush_inline_to_buffer(tree, style_registry, &container_style, children, output );
             }
+            Node::Tag { name } if let Some(token) = PageToken::from_tag(name) => {
+                output.push(tree.add_node(page_token_node(*inherited_style, token)));
+            }
             _ => {}
         }
     }
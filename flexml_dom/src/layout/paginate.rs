@@ -0,0 +1,361 @@
+use crate::layout::fragments::{Fragment, FragmentGroup, FragmentKind, Radius, Rect};
+use crate::layout::tree::{LayoutNode, LayoutNodeKind, LayoutTree};
+use crate::styles::context::{BreakBetween, BreakInside, Float};
+use std::ops::Range;
+use taffy::{Display, FlexDirection, NodeId, Point};
+
+/// Breaks closer than this to the top or bottom of the content are ignored
+const BREAK_EPSILON: f32 = 0.5;
+
+/// Most pages of room given to a multi-column node. Pages with no room would never
+/// hold the content, this stops filling them. It is safe to stop early, columns
+/// past the last room repeat it, they only miss pages sized differently further on
+const MAX_COLUMN_ROOMS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BreakKind {
+    /// A new page starts here
+    Forced,
+    /// Content may continue on a new page here
+    Allowed,
+    /// Only used when a page has no allowed break
+    Avoid,
+}
+
+/// A position in the content flow where a page can end
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct BreakPoint {
+    pub(super) y: f32,
+    pub(super) kind: BreakKind,
+}
+
+/// Break properties of a box. Inline content and columns carry the style of
+/// their block, its breaks apply around the block only
+fn break_sides(node: &LayoutNode) -> (BreakBetween, BreakBetween) {
+    match node.kind {
+        LayoutNodeKind::Container => (node.style_context.break_before(), node.style_context.break_after()),
+        _ => (BreakBetween::Auto, BreakBetween::Auto),
+    }
+}
+
+/// Children of block and column flex containers are stacked and can be split between
+fn is_stacked(node: &LayoutNode) -> bool {
+    match node.style.display {
+        Display::Block => true,
+        Display::Flex => matches!(node.style.flex_direction, FlexDirection::Column | FlexDirection::ColumnReverse),
+        _ => false,
+    }
+}
+
+/// The break before a box, from the box above it and the box itself
+fn push_break_before(tree: &LayoutTree, previous: Option<NodeId>, node_id: NodeId, y: f32, avoid: bool, out: &mut Vec<BreakPoint>) {
+    let (before, _) = break_sides(tree.node_from_id(node_id));
+    let after = previous.map_or(BreakBetween::Auto, |id| break_sides(tree.node_from_id(id)).1);
+
+    let kind = if before == BreakBetween::Page || after == BreakBetween::Page {
+        BreakKind::Forced
+    } else if previous.is_none() {
+        return
+    } else if avoid || before == BreakBetween::Avoid || after == BreakBetween::Avoid {
+        BreakKind::Avoid
+    } else {
+        BreakKind::Allowed
+    };
+
+    out.push(BreakPoint { y, kind });
+}
+
+/// Break points of a root node laid out at `flow_y`, including the break between it and the root before it
+pub(super) fn collect_root_break_points(tree: &LayoutTree, previous: Option<NodeId>, root: NodeId, flow_y: f32, out: &mut Vec<BreakPoint>) {
+    let top = flow_y + tree.node_from_id(root).final_layout.location.y;
+    push_break_before(tree, previous, root, top, false, out);
+    collect_break_points(tree, root, flow_y, false, out);
+}
+
+/// Give the multi-column nodes under a laid out node the room on the page their
+/// columns start on and on the pages after it, from the breaks of the content above
+/// them. Returns whether any were found, their layout and that of their ancestors is cleared
+pub(super) fn fill_column_pages(tree: &mut LayoutTree, node_id: NodeId, offset_y: f32, breaks: &[BreakPoint], page_height: &impl Fn(usize) -> f32) -> bool {
+    let node = tree.node_from_id(node_id);
+    let top = offset_y + node.final_layout.location.y;
+
+    if let LayoutNodeKind::Columns = node.kind {
+        // The balanced columns of the first layout hold all content within their height
+        let used_columns = node.column_rules.len() + 1;
+        let content = 2.0 * used_columns as f32 * node.final_layout.size.height;
+
+        let above: Vec<BreakPoint> = breaks.iter().copied().filter(|b| b.y < top - BREAK_EPSILON).collect();
+        let pages = page_ranges(&above, top, page_height);
+        let page = pages.len() - 1;
+
+        let mut rooms = vec![];
+        let mut room = page_height(page) - (top - pages[page].start);
+        while room.is_finite() && rooms.iter().sum::<f32>() < content && rooms.len() < MAX_COLUMN_ROOMS {
+            rooms.push(room.max(0.0));
+            room = page_height(page + rooms.len());
+        }
+
+        let node = tree.node_from_id_mut(node_id);
+        node.column_pages = rooms;
+        node.cache.clear();
+        return true;
+    }
+
+    let mut found = false;
+    for child_id in node.children.clone() {
+        found |= fill_column_pages(tree, child_id, top, breaks, page_height);
+    }
+    if found {
+        tree.node_from_id_mut(node_id).cache.clear();
+    }
+    found
+}
+
+/// Collect the positions a laid out node can be split at, in flow coordinates.
+/// Breaks go between stacked boxes, between lines of text and between the
+/// column sets of each page. Flex rows and columns are kept whole
+fn collect_break_points(tree: &LayoutTree, node_id: NodeId, offset_y: f32, avoid: bool, out: &mut Vec<BreakPoint>) {
+    let node = tree.node_from_id(node_id);
+    let top = offset_y + node.final_layout.location.y;
+    let avoid = avoid || (matches!(node.kind, LayoutNodeKind::Container) && node.style_context.break_inside() == BreakInside::Avoid);
+
+    match node.kind {
+        LayoutNodeKind::InlineContent => {
+            let Some(inline_layout) = &node.inline_layout else { return };
+            let visible_lines = node.inline_truncation.map_or(usize::MAX, |t| t.lines);
+            let line_count = inline_layout.len().min(visible_lines);
+            let letter_lines = node.inline_initial_letter.as_ref().map_or(0, |letter| letter.lines);
+            let orphans = node.style_context.orphans() as usize;
+            let widows = node.style_context.widows() as usize;
+
+            let floats: Vec<Range<f32>> = node.children.iter()
+                .map(|&id| tree.node_from_id(id))
+                .filter(|child| child.style_context.float() != Float::None)
+                .map(|child| child.final_layout.location.y..child.final_layout.location.y + child.final_layout.size.height)
+                .collect();
+
+            for (index, line) in inline_layout.lines().enumerate().take(line_count).skip(1) {
+                let y = line.metrics().min_coord;
+
+                // Lines beside an initial letter or a float stay with it,
+                // and no fewer lines than the orphans and widows are left on either side
+                let beside = index < letter_lines || floats.iter().any(|float| float.start < y && y < float.end);
+                let stranded = index < orphans || line_count - index < widows;
+                let kind = if avoid || beside || stranded { BreakKind::Avoid } else { BreakKind::Allowed };
+
+                out.push(BreakPoint { y: top + y, kind });
+            }
+        }
+        LayoutNodeKind::Container if is_stacked(node) => {
+            let mut children = node.children.clone();
+            children.sort_by(|a, b| {
+                let a = tree.node_from_id(*a).final_layout.location.y;
+                let b = tree.node_from_id(*b).final_layout.location.y;
+                a.total_cmp(&b)
+            });
+
+            let mut previous = None;
+            for &child_id in &children {
+                let child = tree.node_from_id(child_id);
+                push_break_before(tree, previous, child_id, top + child.final_layout.location.y, avoid, out);
+                collect_break_points(tree, child_id, top, avoid, out);
+                previous = Some(child_id);
+            }
+
+            // A break after the last box ends the page at its bottom
+            if let Some(last) = previous.map(|id| tree.node_from_id(id)) && break_sides(last).1 == BreakBetween::Page {
+                let y = top + last.final_layout.location.y + last.final_layout.size.height;
+                out.push(BreakPoint { y, kind: BreakKind::Forced });
+            }
+        }
+        LayoutNodeKind::Columns => {
+            let kind = if avoid { BreakKind::Avoid } else { BreakKind::Allowed };
+            out.extend(node.column_breaks.iter().map(|y| BreakPoint { y: top + y, kind }));
+        }
+        _ => {}
+    }
+}
+
+/// Split a content flow into page ranges, with the content height of each page.
+///
+/// A page ends at the first forced break on it, otherwise at the last
+/// allowed break that fits, then the last avoided one. Content without
+/// any break that fits is cut at the page height
+pub(super) fn page_ranges(breaks: &[BreakPoint], flow_height: f32, page_height: impl Fn(usize) -> f32) -> Vec<Range<f32>> {
+    let mut breaks = breaks.to_vec();
+    breaks.sort_by(|a, b| a.y.total_cmp(&b.y));
+
+    let mut pages = vec![];
+    let mut start = 0.0;
+
+    loop {
+        // Pages too short for content take the rest of it
+        let page_height = page_height(pages.len());
+        if page_height <= BREAK_EPSILON {
+            pages.push(start..flow_height);
+            break;
+        }
+
+        let limit = start + page_height;
+        let on_page = |kind: BreakKind| breaks.iter()
+            .filter(move |b| b.kind == kind && b.y > start + BREAK_EPSILON && b.y < flow_height - BREAK_EPSILON && b.y <= limit + BREAK_EPSILON)
+            .map(|b| b.y);
+
+        let end = match on_page(BreakKind::Forced).next() {
+            Some(y) => y,
+            None if flow_height <= limit + BREAK_EPSILON => flow_height,
+            None => on_page(BreakKind::Allowed).next_back()
+                .or_else(|| on_page(BreakKind::Avoid).next_back())
+                .unwrap_or(limit),
+        };
+
+        pages.push(start..end);
+        if end >= flow_height - BREAK_EPSILON { break }
+        start = end;
+    }
+
+    pages
+}
+
+/// Range of the flow drawn on a page. Content above the first page
+/// or below the last stays on it
+fn page_span(pages: &[Range<f32>], page: usize) -> Range<f32> {
+    let start = if page == 0 { f32::NEG_INFINITY } else { pages[page].start };
+    let end = if page + 1 == pages.len() { f32::INFINITY } else { pages[page].end };
+    start..end
+}
+
+/// Page that holds the middle of the bounds
+fn page_of(pages: &[Range<f32>], bounds: &Rect) -> usize {
+    let middle = bounds.y + bounds.height / 2.0;
+    pages.iter().position(|page| middle < page.end).unwrap_or(pages.len() - 1)
+}
+
+/// Cut bounds to the part on a page, moved to the page position
+fn cut_bounds(bounds: &Rect, span: &Range<f32>, shift: Point<f32>) -> Rect {
+    let top = bounds.y.clamp(span.start, span.end);
+    let bottom = (bounds.y + bounds.height).clamp(span.start, span.end);
+    Rect::new(bounds.x + shift.x, top + shift.y, bounds.width, bottom - top)
+}
+
+/// Corners are only rounded on the edges of a box that are not cut
+fn cut_radius(radius: Radius, bounds: &Rect, span: &Range<f32>) -> Radius {
+    let top = if bounds.y < span.start { 0.0 } else { 1.0 };
+    let bottom = if bounds.y + bounds.height > span.end { 0.0 } else { 1.0 };
+    Radius::new(radius.top_left * top, radius.top_right * top, radius.bottom_left * bottom, radius.bottom_right * bottom)
+}
+
+/// A box fragment on a page. Corners are only rounded where the box is not cut,
+/// images keep their whole area for sizing and placement
+fn cut_kind(kind: &FragmentKind, bounds: &Rect, span: &Range<f32>, shift: Point<f32>) -> FragmentKind {
+    match *kind {
+        FragmentKind::ColorBackground { color, radius } => FragmentKind::ColorBackground { color, radius: cut_radius(radius, bounds, span) },
+        FragmentKind::ColorBorder { radius, color, weight } => FragmentKind::ColorBorder { radius: cut_radius(radius, bounds, span), color, weight },
+        FragmentKind::Image { image, area, radius, size, position, repeat } => FragmentKind::Image {
+            image,
+            area: Rect::new(area.x + shift.x, area.y + shift.y, area.width, area.height),
+            radius: cut_radius(radius, bounds, span),
+            size,
+            position,
+            repeat,
+        },
+        _ => FragmentKind::Debug,
+    }
+}
+
+/// Split fragments laid out in one flow into pages, moved to the content origin of each page.
+/// Boxes are cut at the page edges, text moves to the page holding its middle
+pub(super) fn paginate_fragments(groups: Vec<FragmentGroup>, pages: &[Range<f32>], origins: &[Point<f32>]) -> Vec<Vec<FragmentGroup>> {
+    let mut out: Vec<Vec<FragmentGroup>> = pages.iter().map(|_| vec![]).collect();
+
+    for group in groups {
+        for (page, split) in split_group(group, pages, origins).into_iter().enumerate() {
+            out[page].extend(split);
+        }
+    }
+
+    out
+}
+
+fn split_group(group: FragmentGroup, pages: &[Range<f32>], origins: &[Point<f32>]) -> Vec<Option<FragmentGroup>> {
+    let shift = |page: usize| Point { x: origins[page].x, y: origins[page].y - pages[page].start };
+    let mut fragments: Vec<Vec<Fragment>> = pages.iter().map(|_| vec![]).collect();
+    let mut subgroups: Vec<Vec<FragmentGroup>> = pages.iter().map(|_| vec![]).collect();
+
+    for fragment in group.fragments {
+        let bounds = fragment.bounds;
+
+        // Text is never cut
+        if let FragmentKind::Text(_) | FragmentKind::TextDecoration { .. } = fragment.kind {
+            let page = page_of(pages, &bounds);
+            let mut bounds = bounds;
+            bounds.x += shift(page).x;
+            bounds.y += shift(page).y;
+            fragments[page].push(Fragment { bounds, kind: fragment.kind });
+            continue;
+        }
+
+        // Boxes cover every page they cross
+        for (page, page_fragments) in fragments.iter_mut().enumerate() {
+            let span = page_span(pages, page);
+            let crosses = if bounds.height > 0.0 {
+                bounds.y < span.end && bounds.y + bounds.height > span.start
+            } else {
+                page_of(pages, &bounds) == page
+            };
+            if !crosses { continue }
+
+            let cut = cut_bounds(&bounds, &span, shift(page));
+            page_fragments.push(Fragment { bounds: cut, kind: cut_kind(&fragment.kind, &bounds, &span, shift(page)) });
+        }
+    }
+
+    for subgroup in group.subgroups {
+        for (page, split) in split_group(subgroup, pages, origins).into_iter().enumerate() {
+            subgroups[page].extend(split);
+        }
+    }
+
+    fragments.into_iter().zip(subgroups).enumerate()
+        .map(|(page, (fragments, subgroups))| {
+            if fragments.is_empty() && subgroups.is_empty() { return None }
+
+            let mut page_group = FragmentGroup::new(cut_bounds(&group.bounds, &page_span(pages, page), shift(page)));
+            page_group.area = Rect::new(group.area.x + shift(page).x, group.area.y + shift(page).y, group.area.width, group.area.height);
+            page_group.fragments = fragments;
+            page_group.subgroups = subgroups;
+            page_group.splittable = group.splittable;
+            page_group.opacity = group.opacity;
+            page_group.rotation = group.rotation;
+            Some(page_group)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_ranges() {
+        let point = |y: f32, kind: BreakKind| BreakPoint { y, kind };
+        let tests = vec![
+            // Fits on one page
+            (vec![point(50.0, BreakKind::Allowed)], 80.0, vec![0.0..80.0]),
+            // Last allowed break that fits
+            (vec![point(40.0, BreakKind::Allowed), point(90.0, BreakKind::Allowed), point(150.0, BreakKind::Allowed)], 200.0, vec![0.0..90.0, 90.0..150.0, 150.0..200.0]),
+            // Allowed breaks win over avoided ones, forced breaks end the page early
+            (vec![point(60.0, BreakKind::Allowed), point(90.0, BreakKind::Avoid), point(120.0, BreakKind::Forced)], 150.0, vec![0.0..60.0, 60.0..120.0, 120.0..150.0]),
+            // Avoided breaks are used before cutting
+            (vec![point(70.0, BreakKind::Avoid)], 150.0, vec![0.0..70.0, 70.0..150.0]),
+            // No break that fits cuts at the page height
+            (vec![point(130.0, BreakKind::Allowed)], 150.0, vec![0.0..100.0, 100.0..150.0]),
+            // A forced break at the very start makes no empty page
+            (vec![point(0.0, BreakKind::Forced)], 50.0, vec![0.0..50.0]),
+        ];
+
+        for (breaks, flow_height, expected) in tests {
+            assert_eq!(page_ranges(&breaks, flow_height, |_| 100.0), expected, "breaks {:?}", breaks);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use crate::layout::fragments::{Fragment, FragmentGroup, FragmentKind, Radius, Rect};
+use crate::layout::sections::PageSetup;
+use crate::layout::FlexmlPage;
+use crate::styles::context::{Color, Dimension};
+
+/// Gap between the trim edge and the crop marks when the bleed is smaller
+const MARK_GAP: Dimension = Dimension::Mm(3.0);
+const MARK_LENGTH: Dimension = Dimension::Mm(5.0);
+/// Space kept around the marks at the canvas edge
+const SLUG_PADDING: Dimension = Dimension::Mm(2.0);
+const MARK_WEIGHT: Dimension = Dimension::Point(0.25);
+const MARK_COLOR: Color = Color(0, 0, 0, 255);
+
+/// Backgrounds closer than this to the trim edge touch it
+const EDGE_EPSILON: f32 = 0.5;
+
+/// Sizes of the print marks of a page setup, in pixels
+struct Marks {
+    gap: f32,
+    length: f32,
+    weight: f32,
+}
+
+impl Marks {
+    fn new(setup: &PageSetup) -> Self {
+        let dpi = setup.style.dpi();
+        let pixels = |dimension: Dimension| dimension.as_pixels(0.0, 0.0, 0.0, dpi);
+
+        Self {
+            gap: pixels(MARK_GAP).max(setup.bleed),
+            length: pixels(MARK_LENGTH),
+            weight: pixels(MARK_WEIGHT).max(1.0),
+        }
+    }
+}
+
+/// Space around the trim box on each side of the rendered page.
+/// It holds the bleed, and the slug with the marks when crop marks are on
+fn canvas_margin(setup: &PageSetup) -> f32 {
+    if !setup.style.crop_marks() { return setup.bleed }
+
+    let marks = Marks::new(setup);
+    marks.gap + marks.length + SLUG_PADDING.as_pixels(0.0, 0.0, 0.0, setup.style.dpi())
+}
+
+/// Extend background colors and images that touch the trim edge into the bleed
+fn extend_into_bleed(groups: &mut [FragmentGroup], setup: &PageSetup) {
+    let bleed = setup.bleed;
+
+    for group in groups {
+        for fragment in &mut group.fragments {
+            let (FragmentKind::ColorBackground { .. } | FragmentKind::Image { .. }) = fragment.kind else { continue };
+            let bounds = &mut fragment.bounds;
+
+            if bounds.x <= EDGE_EPSILON {
+                bounds.width += bounds.x + bleed;
+                bounds.x = -bleed;
+            }
+            if bounds.y <= EDGE_EPSILON {
+                bounds.height += bounds.y + bleed;
+                bounds.y = -bleed;
+            }
+            if bounds.x + bounds.width >= setup.width - EDGE_EPSILON {
+                bounds.width = setup.width + bleed - bounds.x;
+            }
+            if bounds.y + bounds.height >= setup.height - EDGE_EPSILON {
+                bounds.height = setup.height + bleed - bounds.y;
+            }
+
+            // Images on the page edge are placed over the bleed as well
+            if let FragmentKind::Image { area, .. } = &mut fragment.kind {
+                grow_touching_edges(area, setup);
+            }
+        }
+
+        extend_into_bleed(&mut group.subgroups, setup);
+    }
+}
+
+/// Grow the edges of a rect that lie on the trim edge out to the bleed edge
+fn grow_touching_edges(rect: &mut Rect, setup: &PageSetup) {
+    let bleed = setup.bleed;
+    let (right, bottom) = (rect.x + rect.width, rect.y + rect.height);
+
+    if rect.x.abs() <= EDGE_EPSILON { rect.x = -bleed }
+    if rect.y.abs() <= EDGE_EPSILON { rect.y = -bleed }
+    let right = if (right - setup.width).abs() <= EDGE_EPSILON { setup.width + bleed } else { right };
+    let bottom = if (bottom - setup.height).abs() <= EDGE_EPSILON { setup.height + bleed } else { bottom };
+
+    rect.width = right - rect.x;
+    rect.height = bottom - rect.y;
+}
+
+/// Crop marks line up with the trim edges at each corner. Registration marks
+/// are centered on each side, all of them stay outside of the bleed
+fn mark_fragments(setup: &PageSetup, trim: Rect) -> FragmentGroup {
+    let marks = Marks::new(setup);
+    let (gap, length, weight) = (marks.gap, marks.length, marks.weight);
+
+    let mut group = FragmentGroup::new(Rect::new(
+        trim.x - gap - length,
+        trim.y - gap - length,
+        trim.width + 2.0 * (gap + length),
+        trim.height + 2.0 * (gap + length),
+    ));
+
+    let line = |bounds: Rect| Fragment::bg(bounds, Radius::zero(), MARK_COLOR);
+    let (left, top) = (trim.x, trim.y);
+    let (right, bottom) = (trim.x + trim.width, trim.y + trim.height);
+
+    for x in [left, right] {
+        for (y, outward) in [(top, -1.0), (bottom, 1.0)] {
+            let near = y + outward * gap;
+            group.fragments.push(line(Rect::new(x - weight / 2.0, near.min(near + outward * length), weight, length)));
+        }
+    }
+    for y in [top, bottom] {
+        for (x, outward) in [(left, -1.0), (right, 1.0)] {
+            let near = x + outward * gap;
+            group.fragments.push(line(Rect::new(near.min(near + outward * length), y - weight / 2.0, length, weight)));
+        }
+    }
+
+    let middle = gap + length / 2.0;
+    let centers = [
+        (left + trim.width / 2.0, top - middle),
+        (left + trim.width / 2.0, bottom + middle),
+        (left - middle, top + trim.height / 2.0),
+        (right + middle, top + trim.height / 2.0),
+    ];
+
+    for (x, y) in centers {
+        let half = length / 2.0;
+        group.fragments.push(line(Rect::new(x - half, y - weight / 2.0, length, weight)));
+        group.fragments.push(line(Rect::new(x - weight / 2.0, y - half, weight, length)));
+
+        let circle = Rect::new(x - half * 0.6, y - half * 0.6, length * 0.6, length * 0.6);
+        let radius = length * 0.3;
+        group.fragments.push(Fragment::border(circle, Radius::new(radius, radius, radius, radius), MARK_COLOR, weight));
+    }
+
+    group
+}
+
+/// Place the fragments of a page on its printed canvas, with the bleed and print marks around the trim box
+pub(super) fn print_page(setup: &PageSetup, mut fragments: Vec<FragmentGroup>) -> FlexmlPage {
+    let margin = canvas_margin(setup);
+    let trim_box = Rect::new(margin, margin, setup.width, setup.height);
+    let bleed_box = Rect::new(margin - setup.bleed, margin - setup.bleed, setup.width + 2.0 * setup.bleed, setup.height + 2.0 * setup.bleed);
+
+    if setup.bleed > 0.0 {
+        extend_into_bleed(&mut fragments, setup);
+    }
+
+    if margin > 0.0 {
+        for group in &mut fragments {
+            group.translate(margin, margin);
+        }
+    }
+
+    if setup.style.crop_marks() {
+        fragments.push(mark_fragments(setup, trim_box));
+    }
+
+    FlexmlPage {
+        width: setup.width + 2.0 * margin,
+        height: setup.height + 2.0 * margin,
+        trim_box,
+        bleed_box,
+        fragments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::parser::FlexmlDocument;
+
+    fn setup(root: &str) -> PageSetup {
+        let input = format!("{{flexml = pageWidth: 300px + pageHeight: 200px + pixelsPerInch: 192 + padding: 0px + pageBleed: 10px + {root}}}");
+        let document = FlexmlDocument::new(&input)
+            .parse();
+        PageSetup::new(&document.root_style)
+    }
+
+    #[test]
+    fn backgrounds_on_the_trim_edge_grow_into_the_bleed() {
+        let setup = setup("cropMarks: off");
+        assert_eq!(canvas_margin(&setup), 20.0);
+
+        let mut edge = Rect::new(0.0, 100.0, 600.0, 300.0);
+        grow_touching_edges(&mut edge, &setup);
+        assert_eq!(edge, Rect::new(-20.0, 100.0, 640.0, 320.0));
+
+        let mut inside = Rect::new(50.0, 50.0, 100.0, 100.0);
+        grow_touching_edges(&mut inside, &setup);
+        assert_eq!(inside, Rect::new(50.0, 50.0, 100.0, 100.0));
+
+        let mut page = FragmentGroup::new(Rect::new(0.0, 0.0, 600.0, 400.0));
+        page.fragments = vec![
+            Fragment::bg(Rect::new(0.0, 0.0, 600.0, 100.0), Radius::zero(), Color(255, 0, 0, 255)),
+            Fragment::bg(Rect::new(50.0, 150.0, 100.0, 100.0), Radius::zero(), Color(0, 255, 0, 255)),
+        ];
+
+        // Fragments move onto the canvas, the banner covers the bleed above it
+        let printed = print_page(&setup, vec![page]);
+        assert_eq!((printed.width, printed.height), (640.0, 440.0));
+        assert_eq!(printed.trim_box, Rect::new(20.0, 20.0, 600.0, 400.0));
+        assert_eq!(printed.bleed_box, Rect::new(0.0, 0.0, 640.0, 440.0));
+        assert_eq!(printed.fragments[0].fragments[0].bounds, Rect::new(0.0, 0.0, 640.0, 120.0));
+        assert_eq!(printed.fragments[0].fragments[1].bounds, Rect::new(70.0, 170.0, 100.0, 100.0));
+    }
+
+    #[test]
+    fn crop_marks_stay_outside_the_bleed() {
+        let setup = setup("cropMarks: on");
+        let printed = print_page(&setup, vec![]);
+        let bleed = printed.bleed_box;
+
+        let marks = &printed.fragments[0].fragments;
+        assert!(!marks.is_empty());
+        for mark in marks {
+            let b = mark.bounds;
+            let overlaps = b.x < bleed.x + bleed.width && b.x + b.width > bleed.x && b.y < bleed.y + bleed.height && b.y + b.height > bleed.y;
+            assert!(!overlaps, "mark {:?} in bleed {:?}", b, bleed);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::layout::fragments::{collect_fragments, FragmentGroup, Rect};
+use crate::layout::tree::{LayoutNode, LayoutTree};
+use crate::styles::context::StyleContext;
+use taffy::{AvailableSpace, NodeId, Size};
+
+/// Page values that `<page>`, `<pages>` and `<title>` tags stand for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PageToken {
+    Page,
+    Pages,
+    Title,
+}
+
+impl PageToken {
+    pub(super) fn from_tag(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "page" => Some(PageToken::Page),
+            "pages" => Some(PageToken::Pages),
+            "title" => Some(PageToken::Title),
+            _ => None,
+        }
+    }
+}
+
+/// A text node that shows a page value. It stays empty outside of headers and footers
+pub(super) fn page_token_node(style: StyleContext, token: PageToken) -> LayoutNode {
+    let mut node = LayoutNode::new_text(style, String::new());
+    node.page_token = Some(token);
+    node
+}
+
+/// Values of the page tokens for one page
+pub(super) struct PageValues<'a> {
+    pub(super) page: usize,
+    pub(super) pages: usize,
+    pub(super) title: &'a str,
+}
+
+/// Fill in the page tokens of a subtree. Layouts are cleared since the text changed
+fn fill_page_tokens(tree: &mut LayoutTree, node_id: NodeId, values: &PageValues) {
+    let node = tree.node_from_id_mut(node_id);
+    node.cache.clear();
+    node.inline_fit_cache.clear();
+
+    if let Some(token) = node.page_token {
+        node.text = Some(match token {
+            PageToken::Page => values.page.to_string(),
+            PageToken::Pages => values.pages.to_string(),
+            PageToken::Title => values.title.to_string(),
+        });
+    }
+
+    for child_id in node.children.clone() {
+        fill_page_tokens(tree, child_id, values);
+    }
+}
+
+/// Lay out a page region for one page, centered in its band. Headers and footers
+/// get a band of the page padding, overlays and underlays the whole page
+pub(super) fn collect_page_region_fragments(tree: &mut LayoutTree, node_id: NodeId, values: &PageValues, band: Rect, out: &mut Vec<FragmentGroup>) {
+    fill_page_tokens(tree, node_id, values);
+
+    let space = Size { width: AvailableSpace::Definite(band.width), height: AvailableSpace::MaxContent };
+    tree.compute_layout(node_id, space, true);
+
+    let layout = tree.node_from_id(node_id).final_layout;
+    let height = layout.location.y + layout.size.height;
+    let y = band.y + ((band.height - height) / 2.0).max(0.0);
+    collect_fragments(tree, node_id, band.x, y, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::tree::LayoutNodeKind;
+    use crate::layout::FlexmlLayoutContext;
+
+    #[test]
+    fn page_tokens_show_their_page_values() {
+        let mut tree = LayoutTree::new(FlexmlLayoutContext::default());
+        let tokens = ["page", "pages", "title"].map(|tag| {
+            let token = PageToken::from_tag(tag).unwrap();
+            tree.add_node(page_token_node(StyleContext::default(), token))
+        });
+        let root = tree.add_node(LayoutNode::new_container(LayoutNodeKind::Container, StyleContext::default(), tokens.to_vec()));
+
+        let text = |tree: &LayoutTree| tokens.map(|id| tree.node_from_id(id).text.clone().unwrap_or_default());
+
+        fill_page_tokens(&mut tree, root, &PageValues { page: 1, pages: 2, title: "" });
+        assert_eq!(text(&tree), ["1", "2", ""]);
+
+        fill_page_tokens(&mut tree, root, &PageValues { page: 2, pages: 2, title: "Report" });
+        assert_eq!(text(&tree), ["2", "2", "Report"]);
+
+        assert_eq!(PageToken::from_tag("PAGE"), Some(PageToken::Page));
+        assert_eq!(PageToken::from_tag("chapter"), None);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::document::nodes::Node;
+use crate::document::parser::FlexmlDocument;
+use crate::layout::fragments::FragmentGroup;
+use crate::styles::context::{PageRegion, StyleContext, MIN_PAGE_DIMENSION};
+use crate::styles::style::AtomicStyle;
+use taffy::NodeId;
+
+/// Page size and margins of a root style, in pixels
+#[derive(Clone, Copy)]
+pub(super) struct PageSetup {
+    pub(super) style: StyleContext,
+    pub(super) width: f32,
+    /// Height of the page. Auto height pages start out empty and grow with their content
+    pub(super) height: f32,
+    /// The page height is auto, as on a receipt roll
+    pub(super) roll: bool,
+    /// Longest page of a roll, zero when the roll is never cut.
+    /// It always leaves room for the smallest page of content within the padding
+    pub(super) max_height: f32,
+    pub(super) top: f32,
+    pub(super) left: f32,
+    pub(super) bottom: f32,
+    pub(super) right: f32,
+    pub(super) bleed: f32,
+}
+
+impl PageSetup {
+    pub(super) fn new(style: &StyleContext) -> Self {
+        let dpi = style.dpi();
+        let em = style.resolved_font_size();
+        let rem = style.resolved_root_font_size();
+        let none = 0.0f32;
+
+        let width = style.width().as_pixels(none, none, none, dpi);
+        let roll = style.height().is_none();
+        let top = style.padding_top().as_pixels(width, rem, em, dpi);
+        let bottom = style.padding_bottom().as_pixels(width, rem, em, dpi);
+
+        let mut max_height = style.page_max_height().as_pixels(none, rem, em, dpi);
+        if max_height > 0.0 {
+            max_height = max_height.max(top + bottom + MIN_PAGE_DIMENSION);
+        }
+
+        Self {
+            style: *style,
+            width,
+            height: if roll { (top + bottom).max(MIN_PAGE_DIMENSION) } else { style.height().as_pixels(none, none, none, dpi) },
+            roll,
+            max_height,
+            top,
+            left: style.padding_left().as_pixels(width, rem, em, dpi),
+            bottom,
+            right: style.padding_right().as_pixels(width, rem, em, dpi),
+            bleed: style.page_bleed().as_pixels(width, rem, em, dpi),
+        }
+    }
+
+    /// Grow a roll page to fit the content placed on it
+    pub(super) fn fit_content(&mut self, content_height: f32) {
+        if self.roll {
+            self.height = (self.top + content_height + self.bottom).max(MIN_PAGE_DIMENSION);
+        }
+    }
+
+    /// Keep the content width the section was laid out at, taking the right padding from it.
+    /// Left and right pages can move the content but not resize it, so they should mirror their padding
+    pub(super) fn with_content_width(mut self, content_width: f32) -> Self {
+        self.right = (self.width - self.left - content_width).max(0.0);
+        self
+    }
+
+    pub(super) fn content_width(&self) -> f32 {
+        self.width - (self.left + self.right)
+    }
+
+    /// Room for content on a page. Rolls without a maximum height have no limit
+    pub(super) fn content_height(&self) -> f32 {
+        if !self.roll {
+            self.height - (self.top + self.bottom)
+        } else if self.max_height > 0.0 {
+            self.max_height - (self.top + self.bottom)
+        } else {
+            f32::INFINITY
+        }
+    }
+}
+
+/// Pages of a section with their page setup, before headers and footers are added
+pub(super) struct Section {
+    /// Headers and footers declared in the section, used over the document ones
+    pub(super) regions: Vec<(PageRegion, NodeId)>,
+    pub(super) pages: Vec<(PageSetup, Vec<FragmentGroup>)>,
+}
+
+/// Page selectors that apply to a page number. Left and right pages alternate
+/// with the first page on the right, the first page selector applies last
+pub(super) fn page_selectors(page_number: usize) -> Vec<&'static str> {
+    let side = if page_number % 2 == 1 { "right" } else { "left" };
+    if page_number == 1 { vec![side, "first"] } else { vec![side] }
+}
+
+/// Split the top level nodes into sections. Section boxes start a section with
+/// the page styles of the box, the content around them uses the document page style
+pub(super) fn split_sections<'d>(doc: &'d FlexmlDocument) -> Vec<(Option<&'d [AtomicStyle]>, &'d [Node<'d>])> {
+    let mut sections = vec![];
+    let mut start = 0;
+
+    for (index, node) in doc.nodes.iter().enumerate() {
+        let Node::BoxContainer { styles, children } = node else { continue };
+        if !doc.style_registry.is_page_section(styles) { continue }
+
+        if start < index {
+            sections.push((None, &doc.nodes[start..index]));
+        }
+        sections.push((Some(styles.as_slice()), children.as_slice()));
+        start = index + 1;
+    }
+
+    if start < doc.nodes.len() || sections.is_empty() {
+        sections.push((None, &doc.nodes[start..]));
+    }
+
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(root: &str) -> PageSetup {
+        let input = format!("{{flexml = pageWidth: 300px + pageHeight: auto + pixelsPerInch: 96 + {root}}}");
+        let document = FlexmlDocument::new(&input)
+            .parse();
+        PageSetup::new(&document.root_style)
+    }
+
+    #[test]
+    fn roll_pages_keep_the_minimum_page_size() {
+        let mut roll = setup("padding: 0px");
+        assert_eq!(roll.height, MIN_PAGE_DIMENSION);
+        roll.fit_content(10.0);
+        assert_eq!(roll.height, MIN_PAGE_DIMENSION);
+        roll.fit_content(80.0);
+        assert_eq!(roll.height, 80.0);
+
+        // A maximum height inside the padding still leaves room for content
+        let cut = setup("padding: 40px + pageMaxHeight: 20px");
+        assert_eq!(cut.content_height(), MIN_PAGE_DIMENSION);
+        assert_eq!(setup("padding: 40px").content_height(), f32::INFINITY);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::styles::context::{AlignContent, AlignItems, Dimension, Display, FlexDirection, FlexWrap, JustifyContent, StyleContext};
+use crate::styles::context::{AlignContent, AlignItems, Dimension, Direction, Display, FlexDirection, FlexWrap, JustifyContent, StyleContext};
 use std::default::Default;
 
 fn to_taffy_dimension(rem_px: f32, em_px: f32, dpi: f32, dim: Dimension) -> taffy::style::Dimension {
@@ -55,8 +55,13 @@ fn to_taffy_display(display: Display) -> taffy::style::Display {
     }
 }
 
-fn to_taffy_flex_direction(fd: FlexDirection) -> taffy::style::FlexDirection {
+/// Rows flow from the right in rtl containers.
+/// Reversing the row also moves flex-start and flex-end
+/// to the right and left edges
+fn to_taffy_flex_direction(fd: FlexDirection, rtl: bool) -> taffy::style::FlexDirection {
     match fd {
+        FlexDirection::Row if rtl => taffy::style::FlexDirection::RowReverse,
+        FlexDirection::RowReverse if rtl => taffy::style::FlexDirection::Row,
         FlexDirection::Row => taffy::style::FlexDirection::Row,
         FlexDirection::RowReverse => taffy::style::FlexDirection::RowReverse,
         FlexDirection::Column => taffy::style::FlexDirection::Column,
@@ -111,6 +116,7 @@ pub (super) fn style_context_to_taffy(style_context: &StyleContext) -> taffy::st
     let em = style_context.resolved_font_size();
 
     let inline = matches!(style_context.display(), Display::Inline);
+    let rtl = matches!(style_context.direction(), Direction::Rtl);
 
     taffy::style::Style {
         display: to_taffy_display(style_context.display()),
@@ -136,7 +142,7 @@ pub (super) fn style_context_to_taffy(style_context: &StyleContext) -> taffy::st
             bottom: to_taffy_lp(rem, em, dpi, style_context.border_width()),
         },
 
-        flex_direction: to_taffy_flex_direction(style_context.flex_direction()),
+        flex_direction: to_taffy_flex_direction(style_context.flex_direction(), rtl),
         flex_wrap: to_taffy_flex_wrap(style_context.flex_wrap()),
         justify_content: to_taffy_justify_content(style_context.justify_content()),
         align_items: to_taffy_align_items(style_context.align_items()),
@@ -0,0 +1,733 @@
+use crate::layout::inline::{expand_tabs, hyphenate, smart_punctuation, transform_keeping_tabs, transform_with_ws};
+use crate::document::parser::FlexmlDocument;
+use crate::layout::{FlexmlLayout, FlexmlLayoutContext};
+use crate::layout::fragments::{Fragment, FragmentGroup, FragmentKind, Rect};
+use crate::styles::context::{Color, TextTransform, WhiteSpace};
+
+/// Lay out a document the way the renderers get it
+fn lay_out(input: &str) -> FlexmlLayout {
+    let document = FlexmlDocument::new(input)
+        .parse();
+
+    FlexmlLayout::new(&document, FlexmlLayoutContext::default())
+}
+
+/// Lay out a document with its paths relative to the test resources
+fn lay_out_with_images(input: &str) -> FlexmlLayout {
+    let base_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../resources/test");
+    let document = FlexmlDocument::new(input)
+        .with_base_path(base_path)
+        .parse();
+
+    FlexmlLayout::new(&document, FlexmlLayoutContext::default())
+}
+
+/// Values picked from the fragments of groups and their subgroups, in paint order
+fn collect<T>(groups: &[FragmentGroup], pick: &impl Fn(&Fragment) -> Option<T>) -> Vec<T> {
+    let mut out = vec![];
+    for group in groups {
+        out.extend(group.fragments.iter().filter_map(pick));
+        out.extend(collect(&group.subgroups, pick));
+    }
+    out
+}
+
+fn backgrounds(groups: &[FragmentGroup], color: Color) -> Vec<Rect> {
+    collect(groups, &|fragment| match fragment.kind {
+        FragmentKind::ColorBackground { color: c, .. } if c == color => Some(fragment.bounds),
+        _ => None,
+    })
+}
+
+fn text_bounds(groups: &[FragmentGroup]) -> Vec<Rect> {
+    collect(groups, &|fragment| match fragment.kind {
+        FragmentKind::Text(_) => Some(fragment.bounds),
+        _ => None,
+    })
+}
+
+/// Font size and baseline of each glyph run
+fn glyph_runs(groups: &[FragmentGroup]) -> Vec<(f32, f32)> {
+    collect(groups, &|fragment| match &fragment.kind {
+        FragmentKind::Text(run) => Some((run.font_size, run.baseline)),
+        _ => None,
+    })
+}
+
+/// Font size, x and baseline position of each glyph run
+fn text_positions(groups: &[FragmentGroup]) -> Vec<(f32, f32, f32)> {
+    collect(groups, &|fragment| match &fragment.kind {
+        FragmentKind::Text(run) => Some((run.font_size, fragment.bounds.x, fragment.bounds.y + run.baseline)),
+        _ => None,
+    })
+}
+
+/// Glyph ids of all text, in paint order
+fn glyph_ids(groups: &[FragmentGroup]) -> Vec<u16> {
+    collect(groups, &|fragment| match &fragment.kind {
+        FragmentKind::Text(run) => Some(run.glyphs.iter().map(|glyph| glyph.id).collect::<Vec<_>>()),
+        _ => None,
+    }).concat()
+}
+
+/// Clip bounds and box area of each background image
+fn images(groups: &[FragmentGroup]) -> Vec<(Rect, Rect)> {
+    collect(groups, &|fragment| match fragment.kind {
+        FragmentKind::Image { area, .. } => Some((fragment.bounds, area)),
+        _ => None,
+    })
+}
+
+fn decorations(groups: &[FragmentGroup]) -> Vec<(Rect, Color, f32)> {
+    collect(groups, &|fragment| match fragment.kind {
+        FragmentKind::TextDecoration { color, thickness, .. } => Some((fragment.bounds, color, thickness)),
+        _ => None,
+    })
+}
+
+#[test]
+fn test_whitespace_collapsing() {
+    let tests = vec![
+        (WhiteSpace::Normal, "  some   text \n here ", true, " some text here "),
+        (WhiteSpace::NoWrap, "  some   text \n here ", false, "some text here "),
+        (WhiteSpace::Pre, "  some   text \r\n here ", true, "  some   text \n here "),
+        (WhiteSpace::PreWrap, "  some   text \n here ", false, "some   text \n here "),
+        (WhiteSpace::PreLine, "  some   text \n  here ", true, " some text\nhere "),
+        (WhiteSpace::PreLine, "  some   text \r\n  here ", false, "some text\nhere "),
+    ];
+
+    for (ws, input, allow_pre_ws, expected) in tests {
+        let (result, _) = transform_with_ws(input, ws, TextTransform::None, allow_pre_ws);
+        assert_eq!(result, expected, "{:?} input {:?}", ws, input);
+    }
+}
+
+#[test]
+fn test_keeping_tabs() {
+    let tests = vec![
+        (WhiteSpace::Normal, "Coffee \t $4.00", true, "Coffee\t$4.00"),
+        (WhiteSpace::Normal, "  Coffee\t\t  $4.00 ", false, "Coffee\t$4.00 "),
+        (WhiteSpace::Normal, "\tIndented", true, "\tIndented"),
+        (WhiteSpace::PreLine, "a \t b\nc", true, "a\tb\nc"),
+    ];
+
+    for (ws, input, allow_pre_ws, expected) in tests {
+        let (result, _) = transform_keeping_tabs(input, ws, TextTransform::None, allow_pre_ws);
+        assert_eq!(result, expected, "{:?} input {:?}", ws, input);
+    }
+}
+
+#[test]
+fn test_smart_punctuation() {
+    let tests = vec![
+        ("\"Hello,\" she said", None, "\u{201C}Hello,\u{201D} she said"),
+        ("don't 'quote' (\"this\")", None, "don\u{2019}t \u{2018}quote\u{2019} (\u{201C}this\u{201D})"),
+        ("1--2 and---this", None, "1\u{2013}2 and\u{2014}this"),
+        ("Wait... what.. ok.", None, "Wait\u{2026} what.. ok."),
+        ("\" closes", Some('d'), "\u{201D} closes"),
+        ("\"opens", Some(' '), "\u{201C}opens"),
+    ];
+
+    for (input, previous, expected) in tests {
+        assert_eq!(smart_punctuation(input, previous), expected, "input {:?}", input);
+    }
+}
+
+#[test]
+fn test_expand_tabs() {
+    let tests = vec![
+        ("\tcode", 4, 0, "    code"),
+        ("ab\tc", 4, 0, "ab  c"),
+        ("ab\tc", 4, 3, "ab   c"),
+        ("a\n\tb", 2, 5, "a\n  b"),
+        ("a\tb", 0, 0, "ab"),
+    ];
+
+    for (input, tab_size, column, expected) in tests {
+        assert_eq!(expand_tabs(input, tab_size, column), expected, "input {:?}", input);
+    }
+}
+
+#[test]
+fn test_hyphenate() {
+    let tests = vec![
+        ("extensive text", "ex\u{AD}ten\u{AD}sive text"),
+        ("in\u{AD}voice", "in\u{AD}voice"),
+        ("A4 paper", "A4 pa\u{AD}per"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(hyphenate(input, hypher::Lang::English), expected, "input {:?}", input);
+    }
+}
+
+#[test]
+fn inline_block_respects_size_and_margin() {
+    let input = "[Before [display: inline-block + width: 1in + height: 0.5in + marginLeft: 0.25in + bgColor: #FF0000 Box] After]";
+
+    let layout = lay_out(input);
+
+    let bounds = backgrounds(&layout.pages[0].fragments, Color(255, 0, 0, 255));
+
+    assert_eq!(bounds.len(), 1);
+
+    // Default dpi is 160
+    assert_eq!(bounds[0].width, 160.0);
+    assert_eq!(bounds[0].height, 80.0);
+}
+
+#[test]
+fn text_aligns_to_direction() {
+    let input = "[direction: rtl + bgColor: #FF0000 Right] [textAlign: end + direction: rtl Left]";
+
+    let layout = lay_out(input);
+
+    let backgrounds = backgrounds(&layout.pages[0].fragments, Color(255, 0, 0, 255));
+
+    let text = text_bounds(&layout.pages[0].fragments);
+
+    assert_eq!(backgrounds.len(), 1);
+    assert_eq!(text.len(), 2);
+
+    let content = backgrounds[0];
+    assert!((text[0].x + text[0].width - (content.x + content.width)).abs() < 0.01);
+    assert!((text[1].x - content.x).abs() < 0.01);
+}
+
+#[test]
+fn line_clamp_truncates_with_ellipsis() {
+    let words = "Product names in narrow cells wrap over many lines ".repeat(20);
+    let input = format!("[lineClamp: 2 + textOverflow: ellipsis + bgColor: #FF0000 {words}] [whiteSpace: nowrap + textOverflow: ellipsis + bgColor: #00FF00 {words}]");
+
+    let layout = lay_out(&input);
+
+    let clamped = backgrounds(&layout.pages[0].fragments, Color(255, 0, 0, 255));
+
+    let nowrap = backgrounds(&layout.pages[0].fragments, Color(0, 255, 0, 255));
+
+    let text = text_bounds(&layout.pages[0].fragments);
+
+    // Two lines and an ellipsis, then one line and an ellipsis
+    assert_eq!(text.len(), 5);
+    assert!((clamped[0].height - 2.0 * text[0].height).abs() < 0.01);
+
+    for (bounds, content) in [(text[2], clamped[0]), (text[4], nowrap[0])] {
+        assert!(bounds.x + bounds.width <= content.x + content.width + 0.01);
+    }
+}
+#[test]
+fn font_size_fit_fills_box() {
+    let input = "[display: block + width: 2in + height: 0.5in + fontSize: fit + bgColor: #FF0000 Jo] [display: block + width: 2in + height: 0.5in + fontSize: fit + maxFontSize: 10px + bgColor: #00FF00 Jo]";
+
+    let layout = lay_out(input);
+
+    let mut boxes = backgrounds(&layout.pages[0].fragments, Color(255, 0, 0, 255));
+    boxes.extend(backgrounds(&layout.pages[0].fragments, Color(0, 255, 0, 255)));
+
+    let text = text_bounds(&layout.pages[0].fragments);
+
+    assert_eq!(text.len(), 2);
+
+    // The line grows to the height of the box, less than one size step away
+    assert!(text[0].height <= boxes[0].height + 0.01);
+    assert!(text[0].height > boxes[0].height - 1.0);
+
+    // maxFontSize keeps the second line small
+    assert!(text[1].height < boxes[1].height / 2.0);
+}
+
+#[test]
+fn font_size_fit_keeps_room_for_floats() {
+    let input = "[display: block + width: 2in + height: 0.5in + fontSize: fit + bgColor: #FF0000 [float: left + width: 1in + height: 0.5in + bgColor: #00FF00] Jo]";
+
+    let layout = lay_out(input);
+
+    let content = backgrounds(&layout.pages[0].fragments, Color(255, 0, 0, 255));
+    let float = backgrounds(&layout.pages[0].fragments, Color(0, 255, 0, 255));
+    let text = text_bounds(&layout.pages[0].fragments);
+
+    assert_eq!(text.len(), 1);
+
+    // The text is fitted to the room beside the float, not the whole box
+    assert!(text[0].x >= float[0].x + float[0].width - 0.01);
+    assert!(text[0].x + text[0].width <= content[0].x + content[0].width + 0.01);
+}
+#[test]
+fn tab_stops_align_text() {
+    let input = "[display: block + tabStops: \"1in, 3in right\" + leader: dots + bgColor: #FF0000 Item\tName\t$4.00]";
+
+    let layout = lay_out(input);
+
+    let content = backgrounds(&layout.pages[0].fragments, Color(255, 0, 0, 255));
+
+    let text = text_bounds(&layout.pages[0].fragments);
+
+    // Item, Name and the price with leader dots filling both tabs
+    assert_eq!(text.len(), 5);
+    let dpi = layout.dpi;
+    let left = content[0].x;
+
+    assert!((text[2].x - (left + dpi)).abs() < 0.01);
+    assert!((text[4].x + text[4].width - (left + 3.0 * dpi)).abs() < 0.01);
+    assert!(text[3].x >= text[2].x + text[2].width && text[3].x + text[3].width <= text[4].x);
+}
+
+#[test]
+fn decimal_align_lines_up_cells() {
+    let input = "[display: block + width: 2in + bgColor: #FF0000 [display: block + textAlign: decimal(,) 10,25] [display: block + textAlign: decimal(,) 10,5]]";
+
+    let layout = lay_out(input);
+
+    let content = backgrounds(&layout.pages[0].fragments, Color(255, 0, 0, 255));
+
+    let text = text_bounds(&layout.pages[0].fragments);
+
+    // Both cells start with 10, so lined up commas start them at the same x.
+    // The longest part after the comma ends at the right edge
+    assert_eq!(text.len(), 2);
+    assert!((text[0].x - text[1].x).abs() < 0.01);
+    assert!((text[0].x + text[0].width - (content[0].x + content[0].width)).abs() < 0.01);
+}
+
+#[test]
+fn scripts_and_small_caps_are_synthesized() {
+    let input = "[display: block H[sub 2]O x[super 2] [smallCaps Ab]]";
+
+    let layout = lay_out(input);
+
+    let runs = glyph_runs(&layout.pages[0].fragments);
+
+    // H, sub 2, O x, super 2, then small caps A and the smaller B
+    let (size, baseline) = runs[0];
+    let (sub_size, sub_baseline) = runs[1];
+    let (super_size, super_baseline) = runs[3];
+
+    assert!(sub_size < size && sub_baseline > baseline);
+    assert!(super_size < size && super_baseline < baseline);
+    assert!(runs.iter().any(|&(run_size, _)| run_size < size && run_size > super_size));
+}
+
+#[test]
+fn initial_letter_spans_lines() {
+    let input = "{flexml = pageWidth: 300px} [display: block + initialLetter: 2 Once upon a time there was a paragraph long enough to wrap onto several lines of text]";
+
+    let layout = lay_out(input);
+
+    let runs = text_positions(&layout.pages[0].fragments);
+
+    let &(letter_size, letter_x, letter_baseline) = runs.iter()
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap();
+    let text: Vec<_> = runs.iter().filter(|run| run.0 < letter_size).collect();
+
+    // The letter sits on the second line, beside the first two lines
+    assert!(text[0].2 < letter_baseline);
+    assert!((text[1].2 - letter_baseline).abs() < 0.5);
+    assert!(text[0].1 > letter_x && text[1].1 > letter_x);
+    assert!(text[2].1 < text[1].1);
+}
+
+#[test]
+fn columns_are_balanced() {
+    let input = "{flexml = pageWidth: 600px} [display: block + columnCount: 2 + columnRule: 1px one two three four five six seven eight nine ten eleven twelve thirteen fourteen fifteen sixteen seventeen eighteen nineteen twenty twenty-one twenty-two twenty-three twenty-four twenty-five twenty-six twenty-seven twenty-eight twenty-nine thirty]";
+
+    let layout = lay_out(input);
+
+    let runs = text_positions(&layout.pages[0].fragments);
+
+    let first_x = runs[0].1;
+    let (left, right): (Vec<&(f32, f32, f32)>, Vec<_>) = runs.iter().partition(|run| (run.1 - first_x).abs() < 1.0);
+
+    // Both columns start at the top and hold about the same number of lines
+    assert!(!right.is_empty());
+    assert!((left[0].2 - right[0].2).abs() < 0.5);
+    assert!(left.len().abs_diff(right.len()) <= 1);
+}
+
+#[test]
+fn text_wraps_around_floats() {
+    let input = "{flexml = pageWidth: 400px + padding: 0px} [display: block [float: left + width: 100px + height: 40px] one two three four five six seven eight nine ten eleven twelve thirteen fourteen fifteen sixteen seventeen eighteen nineteen twenty twenty-one twenty-two twenty-three twenty-four twenty-five]";
+
+    let layout = lay_out(input);
+
+    let runs = text_positions(&layout.pages[0].fragments);
+
+    // Lines beside the float start past it, lines below it start at the edge
+    let (_, first_x, _) = runs[0];
+    let (_, last_x, _) = runs[runs.len() - 1];
+    assert!(first_x >= 100.0);
+    assert!(last_x < 1.0);
+}
+
+#[test]
+fn blocks_break_across_pages() {
+    let input = "{flexml = pageWidth: 200px + pageHeight: 200px + pixelsPerInch: 96 + padding: 0px}
+        [display: block + height: 120px + bgColor: #FF0000]
+        [display: block + height: 120px + bgColor: #00FF00]
+        <pagebreak>
+        [display: block + height: 20px + bgColor: #0000FF]";
+
+    let layout = lay_out(input);
+    assert_eq!(layout.pages.len(), 3);
+
+    // The block that does not fit and the block after the page break each start a page
+    let green = backgrounds(&layout.pages[1].fragments, Color(0, 255, 0, 255));
+    let blue = backgrounds(&layout.pages[2].fragments, Color(0, 0, 255, 255));
+
+    assert_eq!(green.len(), 1);
+    assert_eq!(green[0].y, 0.0);
+    assert_eq!(blue.len(), 1);
+    assert_eq!(blue[0].y, 0.0);
+}
+
+#[test]
+fn paragraph_splits_keep_widows() {
+    let lines_per_page = |widows: &str| {
+        let input = format!("{{flexml = pageWidth: 200px + pageHeight: 100px + pixelsPerInch: 96 + padding: 0px}}
+            [display: block + lineHeight: 20px + whiteSpace: pre-line {widows}one\ntwo\nthree\nfour\nfive\nsix]");
+
+        let layout = lay_out(&input);
+
+        layout.pages.iter()
+            .map(|page| text_bounds(&page.fragments).len())
+            .collect::<Vec<_>>()
+    };
+
+    // Five lines fit a page, a line is moved to keep two on the next
+    assert_eq!(lines_per_page(""), vec![4, 2]);
+    assert_eq!(lines_per_page("+ widows: 1 "), vec![5, 1]);
+}
+
+#[test]
+fn footer_repeats_on_every_page() {
+    let input = "{flexml = pageWidth: 200px + pageHeight: 200px + pixelsPerInch: 96 + padding: 50px}
+        [footer Page <page> of <pages>]
+        [display: block + height: 60px]
+        <pagebreak>
+        [display: block + height: 60px]";
+
+    let layout = lay_out(input);
+    assert_eq!(layout.pages.len(), 2);
+
+    // The footer is the only text, laid out in the bottom padding of each page
+    for page in &layout.pages {
+        let text = text_bounds(&page.fragments);
+
+        assert!(!text.is_empty());
+        assert!(text.iter().all(|bounds| bounds.y >= 150.0 && bounds.y + bounds.height <= 200.0));
+    }
+
+    // "Page 1 of 2" and "Page 2 of 2" only differ in the page number, which is the page count on the last page
+    let first = glyph_ids(&layout.pages[0].fragments);
+    let last = glyph_ids(&layout.pages[1].fragments);
+    let changed: Vec<usize> = (0..first.len()).filter(|&i| first[i] != last[i]).collect();
+
+    assert_eq!(first.len(), "Page 1 of 2".len());
+    assert_eq!(first.len(), last.len());
+    assert_eq!(changed.len(), 1);
+    assert_eq!(last[changed[0]], last[last.len() - 1]);
+    assert_eq!(first[first.len() - 1], last[last.len() - 1]);
+}
+
+#[test]
+fn title_is_empty_unless_set() {
+    let input = "{flexml = pageWidth: 200px + pageHeight: 200px + pixelsPerInch: 96 + padding: 50px}
+        [header <title>]
+        [display: block + height: 60px]";
+
+    let layout = lay_out(input);
+    assert!(text_bounds(&layout.pages[0].fragments).is_empty());
+
+    let document = FlexmlDocument::new(input)
+        .with_title("Report")
+        .parse();
+
+    let layout = FlexmlLayout::new(&document, FlexmlLayoutContext::default());
+    assert_eq!(glyph_ids(&layout.pages[0].fragments).len(), "Report".len());
+}
+
+#[test]
+fn named_page_sizes() {
+    let page_size = |style: &str| {
+        let input = format!("{{flexml = pixelsPerInch: 100 + {style}}} [Text]");
+        let layout = lay_out(&input);
+        (layout.page_width.round(), layout.page_height.round())
+    };
+
+    assert_eq!(page_size("pageSize: A4"), (827.0, 1169.0));
+    assert_eq!(page_size("pageSize: A4 + pageOrientation: landscape"), (1169.0, 827.0));
+    assert_eq!(page_size("pageSize: letter + pageHeight: 5in"), (850.0, 500.0));
+    assert_eq!(page_size("pageOrientation: landscape"), (1100.0, 850.0));
+    assert_eq!(page_size("pageSize: receipt80mm").0, 315.0);
+}
+
+#[test]
+fn sections_have_their_own_pages() {
+    let input = "{flexml = pixelsPerInch: 100 + pageSize: letter}
+        [Letter page]
+        [section + pageSize: A4 + pageOrientation: landscape + bgColor: #FF0000 Landscape page]
+        [Letter again]";
+
+    let layout = lay_out(input);
+
+    let sizes: Vec<(f32, f32)> = layout.pages.iter().map(|page| (page.width.round(), page.height.round())).collect();
+    assert_eq!(sizes, vec![(850.0, 1100.0), (1169.0, 827.0), (850.0, 1100.0)]);
+
+    // The section background covers its page only
+    let red = backgrounds(&layout.pages[1].fragments, Color(255, 0, 0, 255));
+    assert_eq!(red.len(), 1);
+    assert_eq!((red[0].width.round(), red[0].height.round()), (1169.0, 827.0));
+
+    let red = backgrounds(&layout.pages[0].fragments, Color(255, 0, 0, 255));
+    assert!(red.is_empty());
+}
+
+#[test]
+fn page_selectors_style_pages() {
+    let input = "{flexml = pageWidth: 300px + pageHeight: 200px + pixelsPerInch: 96 + padding: 50px}
+        {flexml:first = pageHeader: none}
+        {flexml:left = paddingLeft: 20px + paddingRight: 80px}
+        {flexml:right = paddingLeft: 80px + paddingRight: 20px}
+        [header Head]
+        [display: block One]
+        <pagebreak>
+        [display: block Two]
+        <pagebreak>
+        [display: block Three]";
+
+    let layout = lay_out(input);
+    assert_eq!(layout.pages.len(), 3);
+
+    let text: Vec<Vec<Rect>> = layout.pages.iter().map(|page| text_bounds(&page.fragments)).collect();
+
+    // The first page has no header
+    assert_eq!(text.iter().map(Vec::len).collect::<Vec<_>>(), vec![1, 2, 2]);
+
+    // Left and right pages mirror their margins, the first page is a right page
+    let left = |text: &[Rect]| text.iter().map(|bounds| bounds.x).fold(f32::INFINITY, f32::min);
+    assert!(left(&text[1]) < left(&text[2]));
+    assert_eq!(left(&text[0]).round(), left(&text[2]).round());
+}
+
+#[test]
+fn page_selectors_keep_the_content_width() {
+    let input = "{flexml = pageWidth: 300px + pageHeight: 200px + pixelsPerInch: 192 + padding: 50px}
+        {flexml:right = paddingLeft: 80px}
+        [display: block + bgColor: #FF0000 One]";
+
+    // The content is laid out at the width of the page style, right pages only move it
+    let layout = lay_out(input);
+    let red = backgrounds(&layout.pages[0].fragments, Color(255, 0, 0, 255));
+    assert_eq!(red.len(), 1);
+    assert_eq!(red[0].x.round(), 160.0);
+    assert_eq!(red[0].width.round(), 400.0);
+}
+
+#[test]
+fn overlays_cover_every_page() {
+    let input = "{flexml = pageWidth: 300px + pageHeight: 200px + pixelsPerInch: 96 + padding: 50px}
+        [overlay + display: block + width: 100px + height: 40px + opacity: 0.5 + rotate: -30 + bgColor: #FF0000]
+        [underlay + display: block + width: 100px + height: 40px + bgColor: #00FF00]
+        [display: block + bgColor: #0000FF One]
+        <pagebreak>
+        [display: block + bgColor: #0000FF Two]";
+
+    let layout = lay_out(input);
+    assert_eq!(layout.pages.len(), 2);
+
+    for page in &layout.pages {
+        // The underlay is painted right after the page background, the overlay last
+        let position = |color: Color| page.fragments.iter().position(|group| {
+            let found = backgrounds(std::slice::from_ref(group), color);
+            !found.is_empty()
+        });
+
+        let underlay = position(Color(0, 255, 0, 255)).unwrap();
+        let content = position(Color(0, 0, 255, 255)).unwrap();
+        let overlay = position(Color(255, 0, 0, 255)).unwrap();
+        assert_eq!(underlay, 1);
+        assert!(underlay < content && content < overlay);
+
+        // Placed against the page box, outside of the page padding
+        let group = &page.fragments[overlay];
+        assert_eq!(group.bounds.x, 0.0);
+        assert_eq!((group.opacity, group.rotation), (0.5, -30.0));
+    }
+}
+
+#[test]
+fn opacity_and_rotation_apply_to_split_boxes() {
+    let input = "{flexml = pageWidth: 200px + pageHeight: 200px + pixelsPerInch: 192 + padding: 0px}
+        [display: block + height: 300px + opacity: 0.5 + rotate: 10 + bgColor: #FF0000]";
+
+    let layout = lay_out(input);
+    assert_eq!(layout.pages.len(), 2);
+
+    fn find(groups: &[FragmentGroup], color: Color) -> Option<&FragmentGroup> {
+        groups.iter().find_map(|group| {
+            let own = group.fragments.iter().any(|fragment| matches!(fragment.kind, FragmentKind::ColorBackground { color: c, .. } if c == color));
+            if own { Some(group) } else { find(&group.subgroups, color) }
+        })
+    }
+
+    let parts: Vec<&FragmentGroup> = layout.pages.iter()
+        .map(|page| find(&page.fragments, Color(255, 0, 0, 255)).unwrap())
+        .collect();
+
+    // Any box takes the styles, each page of it keeps the whole box to turn around
+    for part in &parts {
+        assert_eq!((part.opacity, part.rotation), (0.5, 10.0));
+        assert_eq!(part.area.height, 600.0);
+    }
+    assert_eq!((parts[0].bounds.height, parts[1].bounds.height), (400.0, 200.0));
+    assert_eq!((parts[0].area.y, parts[1].area.y), (0.0, -400.0));
+}
+
+#[test]
+fn bleed_grows_the_page_canvas() {
+    let input = "{flexml = pageWidth: 300px + pageHeight: 200px + pixelsPerInch: 192 + padding: 0px + pageBleed: 10px + cropMarks: on}
+        [display: block + height: 50px + bgColor: #FF0000 Banner]
+        [display: block + padding: 20px [display: block + bgColor: #00FF00 Body]]";
+
+    let layout = lay_out(input);
+    let page = &layout.pages[0];
+
+    // The canvas holds the bleed and the crop marks around the trim box. Pixels are doubled at 192 dpi
+    let (trim, bleed) = (page.trim_box, page.bleed_box);
+    assert_eq!((trim.width, trim.height), (layout.page_width, layout.page_height));
+    assert!(bleed.x > 0.0 && (trim.x - bleed.x - 20.0).abs() < 0.01);
+    assert!(page.width > bleed.width && page.height > bleed.height);
+
+    // Backgrounds on the page edge extend into the bleed, the others stay inside the trim box
+    let banner = backgrounds(&page.fragments, Color(255, 0, 0, 255));
+    assert_eq!((banner[0].x, banner[0].y, banner[0].width), (bleed.x, bleed.y, bleed.width));
+    assert!((banner[0].y + banner[0].height - (trim.y + 100.0)).abs() < 0.01);
+
+    let body = backgrounds(&page.fragments, Color(0, 255, 0, 255));
+    assert!((body[0].x - (trim.x + 40.0)).abs() < 0.01);
+}
+
+#[test]
+fn auto_height_rolls_fit_content() {
+    let roll = |max_height: &str| {
+        let input = format!("{{flexml = pageWidth: 300px + pageHeight: auto + pixelsPerInch: 192 + padding: 10px {max_height}}}
+            [display: block + height: 100px] [display: block + height: 100px] [display: block + height: 100px]");
+        let layout = lay_out(&input);
+        layout.pages.iter().map(|page| page.height.round()).collect::<Vec<_>>()
+    };
+
+    // One page as tall as the content and its padding. Pixels are doubled at 192 dpi
+    assert_eq!(roll(""), vec![640.0]);
+
+    // A maximum height cuts the roll between boxes
+    assert_eq!(roll("+ pageMaxHeight: 250px"), vec![440.0, 240.0]);
+}
+
+#[test]
+fn root_styles_decorate_every_page() {
+    let input = "{flexml = pageWidth: 300px + pageHeight: 200px + pixelsPerInch: 192 + bgColor: #FF0000 + borderColor: #0000FF + borderWidth: 4px + borderRadius: 10px + bgImage: \"out/sample.png\"}
+        [display: block One] <pagebreak> [display: block Two]";
+
+    let layout = lay_out_with_images(input);
+    assert_eq!(layout.pages.len(), 2);
+
+    for page in &layout.pages {
+        let background = &page.fragments[0];
+        let kinds: Vec<&FragmentKind> = background.fragments.iter().map(|fragment| &fragment.kind).collect();
+        assert!(matches!(kinds[..], [FragmentKind::ColorBackground { .. }, FragmentKind::Image { .. }, FragmentKind::ColorBorder { .. }]));
+
+        // The border is drawn inside the page edge
+        let FragmentKind::Image { image, radius, .. } = kinds[1] else { unreachable!() };
+        assert!(layout.paths[*image].ends_with("sample.png"));
+        assert_eq!(radius.top_left, 20.0);
+        assert_eq!(background.fragments[2].bounds.x, 4.0);
+    }
+}
+
+#[test]
+fn background_images_are_cut_by_pages() {
+    let input = "{flexml = pageWidth: 200px + pageHeight: 200px + pixelsPerInch: 192 + padding: 0px}
+        [display: block + height: 300px + bgImage: \"out/sample.png\" + bgSize: cover]";
+
+    let layout = lay_out_with_images(input);
+    assert_eq!(layout.pages.len(), 2);
+
+    // Each page clips the part of the image it holds, the image keeps its whole box
+    let first = images(&layout.pages[0].fragments);
+    let second = images(&layout.pages[1].fragments);
+
+    assert_eq!(first.len(), 1);
+    assert_eq!(second.len(), 1);
+    assert_eq!((first[0].0.y, first[0].0.height), (0.0, 400.0));
+    assert_eq!((second[0].0.y, second[0].0.height), (0.0, 200.0));
+    assert_eq!((first[0].1.y, first[0].1.height), (0.0, 600.0));
+    assert_eq!((second[0].1.y, second[0].1.height), (-400.0, 600.0));
+}
+
+#[test]
+fn columns_break_across_pages() {
+    let blocks = "[display: block + height: 40px + bgColor: #FF0000]".repeat(12);
+    let input = format!("{{flexml = pageWidth: 300px + pageHeight: 300px + pixelsPerInch: 192 + padding: 50px}}
+        [display: block + columnCount: 2 + columnGap: 0px {blocks}]");
+
+    let layout = lay_out(&input);
+    assert_eq!(layout.pages.len(), 2);
+
+    let first = backgrounds(&layout.pages[0].fragments, Color(255, 0, 0, 255));
+    let second = backgrounds(&layout.pages[1].fragments, Color(255, 0, 0, 255));
+
+    // The first page fills both columns, the rest is balanced on the second, no block is cut
+    assert_eq!(first.len(), 10);
+    assert_eq!(second.len(), 2);
+    assert!(first.iter().chain(&second).all(|block| block.height == 80.0));
+    assert!(first.iter().all(|block| block.y + block.height <= 500.0 + 0.5));
+    assert_eq!(second[0].y, second[1].y);
+    assert!(second[0].x != second[1].x);
+}
+
+#[test]
+fn underline_sits_below_the_baseline() {
+    let input = "{flexml = pixelsPerInch: 192} [display: block + color: #0000FF + textDecoration: underline Underlined]";
+
+    let layout = lay_out(input);
+
+    let runs = text_positions(&layout.pages[0].fragments);
+    let text = text_bounds(&layout.pages[0].fragments);
+    let decorations = decorations(&layout.pages[0].fragments);
+
+    assert_eq!(runs.len(), 1);
+    assert_eq!(decorations.len(), 1);
+
+    // The line spans the run just below the baseline, in the font thickness and the text color
+    let (font_size, _, baseline) = runs[0];
+    let (bounds, color, thickness) = decorations[0];
+    assert!(bounds.y > baseline && bounds.y < baseline + font_size * 0.3);
+    assert!(thickness > 0.0 && thickness < font_size * 0.2);
+    assert_eq!(bounds.height, thickness);
+    assert_eq!((bounds.x, bounds.width), (text[0].x, text[0].width));
+    assert_eq!(color, Color(0, 0, 255, 255));
+}
+
+#[test]
+fn decoration_color_and_thickness_override_the_text() {
+    let input = "{flexml = pixelsPerInch: 192}
+        [display: block + textDecoration: underline One]
+        [display: block + textDecoration: underline + decorationColor: #00FF00 + decorationThickness: 4px Two]";
+
+    let layout = lay_out(input);
+
+    let runs = text_positions(&layout.pages[0].fragments);
+    let decorations = decorations(&layout.pages[0].fragments);
+
+    assert_eq!(decorations.len(), 2);
+    let (font_bounds, _, _) = decorations[0];
+    let (bounds, color, thickness) = decorations[1];
+
+    // Custom thicknesses stay centered on the line the font describes
+    assert_eq!(color, Color(0, 255, 0, 255));
+    assert_eq!(thickness, 8.0);
+    assert_eq!(bounds.height, 8.0);
+    let font_center = font_bounds.y + font_bounds.height / 2.0 - runs[0].2;
+    let center = bounds.y + bounds.height / 2.0 - runs[1].2;
+    assert!((font_center - center).abs() < 0.01);
+}
@@ -1,10 +1,13 @@
-use crate::layout::inline::compute_inline_layout;
+use crate::layout::columns::compute_columns_layout;
+use crate::layout::inline::{align_decimal_columns, compute_inline_layout, InitialLetter, InlineTruncation};
 use crate::layout::taffy_style::style_context_to_taffy;
+use crate::layout::running::PageToken;
 use crate::layout::FlexmlLayoutContext;
-use crate::styles::context::StyleContext;
+use crate::styles::context::{PageRegion, StyleContext};
 use crate::styles::context;
+use std::ops::Range;
 use taffy::util::print_tree;
-use taffy::{compute_block_layout, compute_cached_layout, compute_flexbox_layout, compute_root_layout, prelude::*, round_layout, Cache, CacheTree, LayoutOutput};
+use taffy::{compute_block_layout, compute_cached_layout, compute_flexbox_layout, compute_root_layout, prelude::*, round_layout, Cache, CacheTree, LayoutOutput, Point};
 
 #[derive(Debug, Copy, Clone)]
 /// Content is flex and block containers.
@@ -15,6 +18,7 @@ use taffy::{compute_block_layout, compute_cached_layout, compute_flexbox_layout,
 pub(super) enum LayoutNodeKind {
     Container,
     InlineContent, // Inline content
+    Columns, // Content of a multi-column block
     Text //Pure text
 }
 
@@ -25,6 +29,31 @@ pub(super)  struct LayoutNode {
     pub(super) children: Vec<NodeId>,
     pub(super) text: Option<String>,
     pub(super) inline_layout: Option<parley::Layout<[u8; 4]>>,
+    /// Text the inline layout was built from, after whitespace and hyphenation
+    pub(super) inline_text: String,
+    /// Text ranges of the inline layout with the style they were built from
+    pub(super) inline_spans: Vec<(Range<usize>, StyleContext)>,
+    /// Lines hidden by lineClamp and the cut of the last visible line
+    pub(super) inline_truncation: Option<InlineTruncation>,
+    /// Unbroken layouts shaped while searching a `fontSize: fit` size, by size step
+    pub(super) inline_fit_cache: Vec<(u32, parley::Layout<[u8; 4]>)>,
+    /// Sizes of the inline boxes the fit cache was shaped with
+    pub(super) inline_fit_boxes: Vec<f32>,
+    /// Enlarged first letter drawn beside the first lines
+    pub(super) inline_initial_letter: Option<InitialLetter>,
+    /// Horizontal shift of each line that lines up `textAlign: decimal` cells
+    pub(super) inline_line_shifts: Vec<f32>,
+    /// Offset of each line moved into another column than the first line of the content
+    pub(super) inline_line_columns: Vec<Point<f32>>,
+    /// Horizontal position and vertical span of the rule between each pair of filled columns
+    pub(super) column_rules: Vec<(f32, Range<f32>)>,
+    /// Room on each page the column sets are filled to, the last one repeats.
+    /// Columns without rooms are balanced in a single set
+    pub(super) column_pages: Vec<f32>,
+    /// Top of each column set after the first, where a page can end
+    pub(super) column_breaks: Vec<f32>,
+    /// Page value shown by the text, filled in for each page
+    pub(super) page_token: Option<PageToken>,
     pub(crate) cache: Cache,
     pub(crate) unrounded_layout: Layout,
     pub(crate) final_layout: Layout,
@@ -39,6 +68,18 @@ impl LayoutNode {
             children,
             text: None,
             inline_layout: None,
+            inline_text: String::new(),
+            inline_spans: vec![],
+            inline_truncation: None,
+            inline_fit_cache: vec![],
+            inline_fit_boxes: vec![],
+            inline_initial_letter: None,
+            inline_line_shifts: vec![],
+            inline_line_columns: vec![],
+            column_rules: vec![],
+            column_pages: vec![],
+            column_breaks: vec![],
+            page_token: None,
             cache: Default::default(),
             unrounded_layout: Default::default(),
             final_layout: Default::default(),
@@ -53,6 +94,18 @@ impl LayoutNode {
             children: vec![],
             text: Some(text),
             inline_layout: None,
+            inline_text: String::new(),
+            inline_spans: vec![],
+            inline_truncation: None,
+            inline_fit_cache: vec![],
+            inline_fit_boxes: vec![],
+            inline_initial_letter: None,
+            inline_line_shifts: vec![],
+            inline_line_columns: vec![],
+            column_rules: vec![],
+            column_pages: vec![],
+            column_breaks: vec![],
+            page_token: None,
             cache: Default::default(),
             unrounded_layout: Default::default(),
             final_layout: Default::default(),
@@ -63,6 +116,8 @@ impl LayoutNode {
 
 pub(super) struct LayoutTree {
     nodes: Vec<LayoutNode>,
+    /// Headers and footers, repeated on every page
+    pub(super) page_regions: Vec<(PageRegion, NodeId)>,
     pub(super) context: FlexmlLayoutContext,
 }
 
@@ -70,6 +125,7 @@ impl LayoutTree {
     pub fn new(context: FlexmlLayoutContext) -> Self {
         Self {
             nodes: Vec::new(),
+            page_regions: Vec::new(),
             context
         }
     }
@@ -94,6 +150,7 @@ impl LayoutTree {
         if use_rounding {
             round_layout(self, root)
         }
+        align_decimal_columns(self, root);
     }
 
     pub fn print_tree(&mut self, root: NodeId) {
@@ -161,6 +218,10 @@ impl LayoutPartialTree for LayoutTree {
                     compute_inline_layout(tree, node_id, inputs)
                 }
 
+                LayoutNodeKind::Columns => {
+                    compute_columns_layout(tree, node_id, inputs)
+                }
+
                 // Text should not appear outside InlineContent
                 LayoutNodeKind::Text => {
                     LayoutOutput::from_outer_size(Size::ZERO)
@@ -261,6 +322,7 @@ impl PrintTree for LayoutTree {
                 context::Display::Table => "Box (Table)",
             },
             LayoutNodeKind::InlineContent => "Content",
+            LayoutNodeKind::Columns => "Columns",
             LayoutNodeKind::Text => "Text",
         }
     }
@@ -15,6 +15,10 @@ impl ValueHelp {
     pub const IMAGE: &'static [&'static str] = &["file.png", "/assets/image.jpg", "../image.png", "drawing.svg"];
     pub const FONT: &'static [&'static str] = &["helvetica.ttf", "/fonts/bebas.otf",];
     pub const FONT_EMPTY: &'static [&'static str] = &["fonts/bebas.otf", "helveltica*.ttf"];
+    pub const FONT_FEATURES: &'static [&'static str] = &["\"tnum\"", "\"tnum\" \"ss01\" -liga", "\"salt=2\""];
+    pub const FONT_VARIATIONS: &'static [&'static str] = &["wght 650", "wght 650 wdth 90"];
+    pub const DECIMAL: &'static [&'static str] = &["decimal", "decimal(,)", "\"decimal(·)\""];
+    pub const TAB_STOPS: &'static [&'static str] = &["2in", "\"1in, 3in right\"", "\"50% center, 90% decimal\""];
 }
 
 pub struct ValueErrors;
@@ -33,6 +37,12 @@ impl ValueErrors {
     pub const IMAGE: &'static str = "Invalid image file path";
     pub const FONT: &'static str = "Invalid font file path";
     pub const FONT_EMPTY: &'static str = "Font path had no fonts";
+    pub const FONT_FEATURES: &'static str = "Invalid font feature";
+    pub const FONT_VARIATIONS: &'static str = "Invalid font variation";
+    pub const FONT_SETTINGS_FULL: &'static str = "Too many font settings";
+    pub const TAB_STOPS: &'static str = "Invalid tab stop";
+    pub const TAB_STOPS_FULL: &'static str = "Too many tab stops";
+    pub const DECIMAL: &'static str = "Decimal alignment takes a single character";
 }
 
 pub struct Chars;
@@ -47,6 +57,7 @@ impl Chars {
     pub const PT: &'static str = "pt";
     pub const HEX: &'static str = "#";
     pub const FORWARD: &'static str = ">";
+    pub const DECIMAL_OPEN: &'static str = "decimal(";
 }
 
 
@@ -0,0 +1,106 @@
+use crate::styles::context::{BreakBetween, BreakInside, StyleContext};
+use crate::styles::builtin::{style_context_match, BuiltInStyle};
+use crate::styles::style::StyleValue;
+use crate::styles::style::StyleValueParser::{Float, Match};
+
+const BREAK_BETWEEN_VARIANTS: &[BreakBetween] = &[
+    BreakBetween::Auto,
+    BreakBetween::Page,
+    BreakBetween::Avoid,
+];
+
+pub static BREAK_BETWEEN_MATCHES: &[&str] = &[
+    "auto",
+    "page",
+    "avoid",
+];
+
+fn apply_break_before(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, BREAK_BETWEEN_VARIANTS) {
+        context.set_break_before(v);
+    }
+}
+
+/// Starts a new page before the box, or keeps it on the page of the content before it
+pub static BREAK_BEFORE: BuiltInStyle = BuiltInStyle {
+    name: "breakBefore",
+    parser: Match(BREAK_BETWEEN_MATCHES),
+    styles: &[],
+    apply_style: apply_break_before,
+};
+
+fn apply_break_after(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, BREAK_BETWEEN_VARIANTS) {
+        context.set_break_after(v);
+    }
+}
+
+/// Starts a new page after the box, or keeps it on the page of the content after it
+pub static BREAK_AFTER: BuiltInStyle = BuiltInStyle {
+    name: "breakAfter",
+    parser: Match(BREAK_BETWEEN_MATCHES),
+    styles: &[],
+    apply_style: apply_break_after,
+};
+
+
+const BREAK_INSIDE_VARIANTS: &[BreakInside] = &[
+    BreakInside::Auto,
+    BreakInside::Avoid,
+];
+
+pub static BREAK_INSIDE_MATCHES: &[&str] = &[
+    "auto",
+    "avoid",
+];
+
+fn apply_break_inside(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, BREAK_INSIDE_VARIANTS) {
+        context.set_break_inside(v);
+    }
+}
+
+/// Keeps a box on one page when it fits
+pub static BREAK_INSIDE: BuiltInStyle = BuiltInStyle {
+    name: "breakInside",
+    parser: Match(BREAK_INSIDE_MATCHES),
+    styles: &[],
+    apply_style: apply_break_inside,
+};
+
+
+/// Line count of widows and orphans, at least one line
+fn line_count(value: &StyleValue) -> Option<u16> {
+    match value {
+        StyleValue::Float(f) if *f >= 1.0 => Some(f.round().min(u16::MAX as f32) as u16),
+        _ => None,
+    }
+}
+
+fn apply_widows(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(lines) = line_count(value) {
+        context.set_widows(lines);
+    }
+}
+
+/// Fewest lines of a paragraph carried to the top of a new page
+pub static WIDOWS: BuiltInStyle = BuiltInStyle {
+    name: "widows",
+    parser: Float,
+    styles: &[],
+    apply_style: apply_widows,
+};
+
+fn apply_orphans(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(lines) = line_count(value) {
+        context.set_orphans(lines);
+    }
+}
+
+/// Fewest lines of a paragraph left at the bottom of a page
+pub static ORPHANS: BuiltInStyle = BuiltInStyle {
+    name: "orphans",
+    parser: Float,
+    styles: &[],
+    apply_style: apply_orphans,
+};
@@ -0,0 +1,76 @@
+use crate::styles::context::{Dimension, StyleContext};
+use crate::styles::builtin::{length_to_context, style_context_color, BuiltInStyle};
+use crate::styles::style::StyleValue;
+use crate::styles::style::StyleValueParser::{Color, MatchOrFloat, MatchOrPositiveNumber, PositiveNumber};
+
+fn apply_column_count(value: &StyleValue, context: &mut StyleContext) {
+    match value {
+        StyleValue::Match(..) => context.set_column_count(0),
+        StyleValue::Float(f) if *f >= 1.0 => context.set_column_count(f.round().min(u16::MAX as f32) as u16),
+        _ => {}
+    }
+}
+
+pub static COLUMN_COUNT_MATCHES: &[&str] = &[
+    "auto",
+];
+
+/// Number of columns the content of a block flows through
+pub static COLUMN_COUNT: BuiltInStyle = BuiltInStyle {
+    name: "columnCount",
+    parser: MatchOrFloat(COLUMN_COUNT_MATCHES),
+    styles: &[],
+    apply_style: apply_column_count,
+};
+
+
+const COLUMN_WIDTH_VARIANTS: &[Dimension] = &[
+    Dimension::Auto,
+];
+
+fn apply_column_width(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(d) = length_to_context(value, COLUMN_WIDTH_VARIANTS) {
+        context.set_column_width(d);
+    }
+}
+
+pub static COLUMN_WIDTH_MATCHES: &[&str] = &[
+    "auto",
+];
+
+/// Ideal width of a column, as many columns as fit are made
+pub static COLUMN_WIDTH: BuiltInStyle = BuiltInStyle {
+    name: "columnWidth",
+    parser: MatchOrPositiveNumber(COLUMN_WIDTH_MATCHES),
+    styles: &[],
+    apply_style: apply_column_width,
+};
+
+
+fn apply_column_rule(value: &StyleValue, context: &mut StyleContext) {
+    if let StyleValue::PositiveNumber(d) = value {
+        context.set_column_rule_width(*d);
+    }
+}
+
+/// Width of the line drawn between columns
+pub static COLUMN_RULE: BuiltInStyle = BuiltInStyle {
+    name: "columnRule",
+    parser: PositiveNumber,
+    styles: &[],
+    apply_style: apply_column_rule,
+};
+
+
+fn apply_column_rule_color(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(color) = style_context_color(value) {
+        context.set_column_rule_color(color);
+    }
+}
+
+pub static COLUMN_RULE_COLOR: BuiltInStyle = BuiltInStyle {
+    name: "columnRuleColor",
+    parser: Color,
+    styles: &[],
+    apply_style: apply_column_rule_color,
+};
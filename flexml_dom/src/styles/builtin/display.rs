@@ -1,4 +1,4 @@
-use crate::styles::context::{Display, StyleContext};
+use crate::styles::context::{Clear, Display, Float, StyleContext};
 use crate::styles::builtin::{match_value, style_context_match, BuiltInStyle};
 use crate::styles::style::{StyleValue};
 use crate::styles::style::StyleValueParser::Match;
@@ -40,3 +40,58 @@ pub static DISPLAY: BuiltInStyle = BuiltInStyle {
     apply_style: apply_display,
 };
 
+
+const FLOAT_VARIANTS: &[Float] = &[
+    Float::None,
+    Float::Left,
+    Float::Right,
+];
+
+fn apply_float(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, FLOAT_VARIANTS) {
+        context.set_float(v);
+    }
+}
+
+pub static FLOAT_MATCHES: &[&str] = &[
+    "none",
+    "left",
+    "right",
+];
+
+/// Takes a box out of the flow to the side of the text it is in, the text wraps around it
+pub static FLOAT: BuiltInStyle = BuiltInStyle {
+    name: "float",
+    parser: Match(FLOAT_MATCHES),
+    styles: &[],
+    apply_style: apply_float,
+};
+
+
+const CLEAR_VARIANTS: &[Clear] = &[
+    Clear::None,
+    Clear::Left,
+    Clear::Right,
+    Clear::Both,
+];
+
+fn apply_clear(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, CLEAR_VARIANTS) {
+        context.set_clear(v);
+    }
+}
+
+pub static CLEAR_MATCHES: &[&str] = &[
+    "none",
+    "left",
+    "right",
+    "both",
+];
+
+/// Moves a box below the floats on the cleared sides
+pub static CLEAR: BuiltInStyle = BuiltInStyle {
+    name: "clear",
+    parser: Match(CLEAR_MATCHES),
+    styles: &[],
+    apply_style: apply_clear,
+};
@@ -6,12 +6,15 @@ pub mod white_space;
 pub mod margin;
 pub mod padding;
 pub mod opacity;
+pub mod rotate;
 pub mod flex;
 pub mod border;
 pub mod bg;
 pub mod text;
 pub mod dimensions;
 pub mod page;
+pub mod columns;
+pub mod breaks;
 
 pub struct BuiltInStyle {
     pub name: &'static str,
@@ -75,14 +78,41 @@ const fn match_value(idx: u8, matches: &'static [&'static str]) -> StyleValue {
 
 pub static ROOT_STYLE_NAME: &str = "flexml";
 
+/// Page selectors of root style definitions, `{flexml:first = ...}`
+pub static PAGE_SELECTORS: &[&str] = &["first", "left", "right"];
+
 pub static DEFAULT_BUILTINS : &[&BuiltInStyle] = &[
     &page::PAGE_HEIGHT,
+    &page::PAGE_MAX_HEIGHT,
     &page::PAGE_WIDTH,
     &page::PAGE_DPI,
+    &page::PAGE_SIZE,
+    &page::PAGE_ORIENTATION,
+    &page::SMART_PUNCTUATION,
+    &page::PAGE_REGION,
+    &page::PAGE_SECTION,
+    &page::PAGE_HEADER,
+    &page::PAGE_FOOTER,
+    &page::PAGE_BLEED,
+    &page::CROP_MARKS,
 
     &display::DISPLAY,
+    &display::FLOAT,
+    &display::CLEAR,
+    &breaks::BREAK_BEFORE,
+    &breaks::BREAK_AFTER,
+    &breaks::BREAK_INSIDE,
+    &breaks::WIDOWS,
+    &breaks::ORPHANS,
     &white_space::WHITE_SPACE,
+    &white_space::TAB_SIZE,
+    &white_space::TAB_STOPS,
+    &white_space::LEADER,
+    &white_space::HYPHENS,
+    &white_space::LINE_CLAMP,
+    &white_space::TEXT_OVERFLOW,
     &opacity::OPACITY,
+    &rotate::ROTATE,
 
     &margin::MARGIN,
     &margin::MARGIN_TOP,
@@ -109,6 +139,11 @@ pub static DEFAULT_BUILTINS : &[&BuiltInStyle] = &[
     &flex::JUSTIFY_CONTENT,
     &flex::FLEX_WRAP,
 
+    &columns::COLUMN_COUNT,
+    &columns::COLUMN_WIDTH,
+    &columns::COLUMN_RULE,
+    &columns::COLUMN_RULE_COLOR,
+
     &dimensions::WIDTH,
     &dimensions::MAX_WIDTH,
     &dimensions::MIN_WIDTH,
@@ -117,16 +152,28 @@ pub static DEFAULT_BUILTINS : &[&BuiltInStyle] = &[
     &dimensions::MIN_HEIGHT,
 
     &text::TEXT_ALIGN,
+    &text::DIRECTION,
     &text::TEXT_COLOR,
     &text::TEXT_DECORATION,
+    &text::TEXT_DECORATION_COLOR,
+    &text::TEXT_DECORATION_THICKNESS,
+    &text::TEXT_DECORATION_STYLE,
     &text::TEXT_FONT,
     &text::TEXT_SIZE,
+    &text::MIN_FONT_SIZE,
+    &text::MAX_FONT_SIZE,
     &text::TEXT_STYLE,
     &text::TEXT_TRANSFORM,
+    &text::VERTICAL_ALIGN,
+    &text::FONT_VARIANT,
+    &text::INITIAL_LETTER,
     &text::TEXT_LETTER_SPACING,
     &text::TEXT_LINE_HEIGHT,
     &text::TEXT_WEIGHT,
+    &text::FONT_FEATURES,
+    &text::FONT_VARIATIONS,
     &text::TEXT_WORD_SPACING,
+    &text::LANG,
 
     &bg::BG_COLOR,
     &bg::BG_IMAGE,
@@ -1,6 +1,6 @@
-use crate::styles::builtin::{dimension_to_context, float_to_context, BuiltInStyle};
-use crate::styles::context::StyleContext;
-use crate::styles::style::StyleValueParser::{Float, PositiveNumber};
+use crate::styles::builtin::{dimension_to_context, float_to_context, length_to_context, match_value, style_context_match, BuiltInStyle};
+use crate::styles::context::{Dimension, PageOrientation, PageRegion, PageSize, StyleContext};
+use crate::styles::style::StyleValueParser::{Float, Match, MatchOrPositiveNumber, PositiveNumber};
 use crate::styles::style::StyleValue;
 
 fn apply_page_width(value: &StyleValue, context: &mut StyleContext) {
@@ -17,20 +17,44 @@ pub static PAGE_WIDTH: BuiltInStyle = BuiltInStyle {
 };
 
 
+const PAGE_HEIGHT_VARIANTS: &[Dimension] = &[
+    Dimension::Auto,
+];
+
 fn apply_page_height(value: &StyleValue, context: &mut StyleContext) {
-    if context.is_root() && let Some(d) = dimension_to_context(value) {
+    if context.is_root() && let Some(d) = length_to_context(value, PAGE_HEIGHT_VARIANTS) {
         context.set_height(d);
     }
 }
 
+pub static PAGE_HEIGHT_MATCHES: &[&str] = &[
+    "auto",
+];
+
+/// Auto height makes a continuous roll, each page is as tall as its content
 pub static PAGE_HEIGHT: BuiltInStyle = BuiltInStyle {
     name: "pageHeight",
-    parser: PositiveNumber,
+    parser: MatchOrPositiveNumber(PAGE_HEIGHT_MATCHES),
     styles: &[],
     apply_style: apply_page_height,
 };
 
 
+fn apply_page_max_height(value: &StyleValue, context: &mut StyleContext) {
+    if context.is_root() && let Some(d) = dimension_to_context(value) {
+        context.set_page_max_height(d);
+    }
+}
+
+/// Longest page of an auto height roll, longer content is cut into more pages
+pub static PAGE_MAX_HEIGHT: BuiltInStyle = BuiltInStyle {
+    name: "pageMaxHeight",
+    parser: PositiveNumber,
+    styles: &[],
+    apply_style: apply_page_max_height,
+};
+
+
 fn apply_page_dpi(value: &StyleValue, context: &mut StyleContext) {
     if context.is_root() && let Some(f) = float_to_context(value) {
         context.set_dpi(f);
@@ -44,3 +68,219 @@ pub static PAGE_DPI: BuiltInStyle = BuiltInStyle {
     apply_style: apply_page_dpi,
 };
 
+
+fn apply_smart_punctuation(value: &StyleValue, context: &mut StyleContext) {
+    if context.is_root() && let Some(v) = style_context_match(value, &[false, true]) {
+        context.set_smart_punctuation(v);
+    }
+}
+
+pub static SMART_PUNCTUATION_MATCHES: &[&str] = &[
+    "off",
+    "on",
+];
+
+pub static SMART_PUNCTUATION: BuiltInStyle = BuiltInStyle {
+    name: "smartPunctuation",
+    parser: Match(SMART_PUNCTUATION_MATCHES),
+    styles: &[],
+    apply_style: apply_smart_punctuation,
+};
+
+
+
+const PAGE_SIZE_VARIANTS: &[PageSize] = &[
+    PageSize::A3,
+    PageSize::A4,
+    PageSize::A5,
+    PageSize::A6,
+    PageSize::B5,
+    PageSize::Letter,
+    PageSize::Legal,
+    PageSize::Tabloid,
+    PageSize::Executive,
+    PageSize::Receipt58mm,
+    PageSize::Receipt80mm,
+];
+
+fn apply_page_size(value: &StyleValue, context: &mut StyleContext) {
+    if context.is_root() && let Some(v) = style_context_match(value, PAGE_SIZE_VARIANTS) {
+        context.set_page_size(v);
+    }
+}
+
+pub static PAGE_SIZE_MATCHES: &[&str] = &[
+    "A3",
+    "A4",
+    "A5",
+    "A6",
+    "B5",
+    "letter",
+    "legal",
+    "tabloid",
+    "executive",
+    "receipt58mm",
+    "receipt80mm",
+];
+
+/// Named paper size, pageWidth and pageHeight still override it
+pub static PAGE_SIZE: BuiltInStyle = BuiltInStyle {
+    name: "pageSize",
+    parser: Match(PAGE_SIZE_MATCHES),
+    styles: &[],
+    apply_style: apply_page_size,
+};
+
+
+const PAGE_ORIENTATION_VARIANTS: &[PageOrientation] = &[
+    PageOrientation::Portrait,
+    PageOrientation::Landscape,
+];
+
+fn apply_page_orientation(value: &StyleValue, context: &mut StyleContext) {
+    if context.is_root() && let Some(v) = style_context_match(value, PAGE_ORIENTATION_VARIANTS) {
+        context.set_page_orientation(v);
+    }
+}
+
+pub static PAGE_ORIENTATION_MATCHES: &[&str] = &[
+    "portrait",
+    "landscape",
+];
+
+pub static PAGE_ORIENTATION: BuiltInStyle = BuiltInStyle {
+    name: "pageOrientation",
+    parser: Match(PAGE_ORIENTATION_MATCHES),
+    styles: &[],
+    apply_style: apply_page_orientation,
+};
+
+
+fn apply_page_section(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, &[false, true]) {
+        context.set_page_section(v);
+    }
+}
+
+pub static PAGE_SECTION_MATCHES: &[&str] = &[
+    "off",
+    "on",
+];
+
+/// A top level box that starts a new page with its own page setup.
+/// Page styles on the box apply to the pages of the section
+pub static PAGE_SECTION: BuiltInStyle = BuiltInStyle {
+    name: "pageSection",
+    parser: Match(PAGE_SECTION_MATCHES),
+    styles: &[
+        ("section", match_value(1, PAGE_SECTION_MATCHES)),
+    ],
+    apply_style: apply_page_section,
+};
+
+
+pub static PAGE_REGION_SHOWN_MATCHES: &[&str] = &[
+    "none",
+    "auto",
+];
+
+fn apply_page_header(value: &StyleValue, context: &mut StyleContext) {
+    if context.is_root() && let Some(v) = style_context_match(value, &[false, true]) {
+        context.set_page_header(v);
+    }
+}
+
+/// Hides the header on the pages of a page selector or section
+pub static PAGE_HEADER: BuiltInStyle = BuiltInStyle {
+    name: "pageHeader",
+    parser: Match(PAGE_REGION_SHOWN_MATCHES),
+    styles: &[],
+    apply_style: apply_page_header,
+};
+
+fn apply_page_footer(value: &StyleValue, context: &mut StyleContext) {
+    if context.is_root() && let Some(v) = style_context_match(value, &[false, true]) {
+        context.set_page_footer(v);
+    }
+}
+
+/// Hides the footer on the pages of a page selector or section
+pub static PAGE_FOOTER: BuiltInStyle = BuiltInStyle {
+    name: "pageFooter",
+    parser: Match(PAGE_REGION_SHOWN_MATCHES),
+    styles: &[],
+    apply_style: apply_page_footer,
+};
+
+fn apply_page_bleed(value: &StyleValue, context: &mut StyleContext) {
+    if context.is_root() && let Some(d) = dimension_to_context(value) {
+        context.set_page_bleed(d);
+    }
+}
+
+/// Grows the rendered page past its trim size on every side.
+/// Backgrounds touching the page edge extend into it
+pub static PAGE_BLEED: BuiltInStyle = BuiltInStyle {
+    name: "pageBleed",
+    parser: PositiveNumber,
+    styles: &[],
+    apply_style: apply_page_bleed,
+};
+
+fn apply_crop_marks(value: &StyleValue, context: &mut StyleContext) {
+    if context.is_root() && let Some(v) = style_context_match(value, &[false, true, false, true]) {
+        context.set_crop_marks(v);
+    }
+}
+
+pub static CROP_MARKS_MATCHES: &[&str] = &[
+    "off",
+    "on",
+    "false",
+    "true",
+];
+
+/// Draws crop and registration marks around the page, outside of the bleed
+pub static CROP_MARKS: BuiltInStyle = BuiltInStyle {
+    name: "cropMarks",
+    parser: Match(CROP_MARKS_MATCHES),
+    styles: &[],
+    apply_style: apply_crop_marks,
+};
+
+const PAGE_REGION_VARIANTS: &[PageRegion] = &[
+    PageRegion::None,
+    PageRegion::Header,
+    PageRegion::Footer,
+    PageRegion::Overlay,
+    PageRegion::Underlay,
+];
+
+fn apply_page_region(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, PAGE_REGION_VARIANTS) {
+        context.set_page_region(v);
+    }
+}
+
+pub static PAGE_REGION_MATCHES: &[&str] = &[
+    "none",
+    "header",
+    "footer",
+    "overlay",
+    "underlay",
+];
+
+/// Takes a box out of the flow and repeats it in the top or bottom padding of every page,
+/// or over or under the whole page box for overlays and underlays.
+/// `<page>`, `<pages>` and `<title>` inside it show the page number, page count and document name
+pub static PAGE_REGION: BuiltInStyle = BuiltInStyle {
+    name: "pageRegion",
+    parser: Match(PAGE_REGION_MATCHES),
+    styles: &[
+        ("header", match_value(1, PAGE_REGION_MATCHES)),
+        ("footer", match_value(2, PAGE_REGION_MATCHES)),
+        ("overlay", match_value(3, PAGE_REGION_MATCHES)),
+        ("underlay", match_value(4, PAGE_REGION_MATCHES)),
+    ],
+    apply_style: apply_page_region,
+};
@@ -0,0 +1,18 @@
+use crate::styles::context::StyleContext;
+use crate::styles::builtin::{float_to_context, BuiltInStyle};
+use crate::styles::style::{StyleValue};
+use crate::styles::style::StyleValueParser::{Float};
+
+fn apply_rotate(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(f) = float_to_context(value) {
+        context.set_rotate(f);
+    }
+}
+
+/// Rotates a box clockwise by degrees around its center when painted. Layout is not affected
+pub static ROTATE: BuiltInStyle = BuiltInStyle {
+    name: "rotate",
+    parser: Float,
+    styles: &[],
+    apply_style: apply_rotate,
+};
@@ -1,8 +1,8 @@
-use crate::styles::context::{FontFamily, FontStyle, StyleContext, TextAlign, TextDecoration, TextTransform};
+use crate::styles::context::{Direction, FontFamily, FontStyle, FontVariant, StyleContext, TextAlign, TextDecoration, TextDecorationStyle, TextTransform, VerticalAlign};
 use crate::styles::builtin::{dimension_to_context, match_value, style_context_color, style_context_match, BuiltInStyle};
 use crate::styles::style::{PathType, StyleValue};
 use crate::styles::style::StyleValue::{Forward};
-use crate::styles::style::StyleValueParser::{Color, Match, Path, PositiveNumber};
+use crate::styles::style::StyleValueParser::{Color, FontFeatures, FontVariations, Match, MatchOrDecimal, MatchOrFloat, MatchOrPositiveNumber, Path, PositiveNumber};
 
 fn apply_text_color(value: &StyleValue, context: &mut StyleContext) {
     if let Some(color) = style_context_color(value) {
@@ -36,19 +36,61 @@ pub static TEXT_FONT: BuiltInStyle = BuiltInStyle {
 
 
 fn apply_text_size(value: &StyleValue, context: &mut StyleContext) {
-    if let Some(d) = dimension_to_context(value) {
+    if let StyleValue::Match(..) = value {
+        context.set_font_size_fit(true);
+    } else if let Some(d) = dimension_to_context(value) {
         context.set_font_size(d);
+        context.set_font_size_fit(false);
     }
 }
 
+pub static TEXT_SIZE_MATCHES: &[&str] = &[
+    "fit",
+];
+
+/// `fit` keeps the inherited size as the base and scales the inline
+/// content to the largest size that fits the box
 pub static TEXT_SIZE: BuiltInStyle = BuiltInStyle {
     name: "fontSize",
-    parser: PositiveNumber,
-    styles: &[ ("textSize", Forward) ],
+    parser: MatchOrPositiveNumber(TEXT_SIZE_MATCHES),
+    styles: &[
+        ("textSize", Forward),
+        ("textFit", match_value(0, TEXT_SIZE_MATCHES)),
+    ],
     apply_style: apply_text_size,
 };
 
 
+fn apply_min_font_size(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(d) = dimension_to_context(value) {
+        context.set_min_font_size(d);
+    }
+}
+
+/// Smallest size `fontSize: fit` may shrink to
+pub static MIN_FONT_SIZE: BuiltInStyle = BuiltInStyle {
+    name: "minFontSize",
+    parser: PositiveNumber,
+    styles: &[],
+    apply_style: apply_min_font_size,
+};
+
+
+fn apply_max_font_size(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(d) = dimension_to_context(value) {
+        context.set_max_font_size(d);
+    }
+}
+
+/// Largest size `fontSize: fit` may grow to
+pub static MAX_FONT_SIZE: BuiltInStyle = BuiltInStyle {
+    name: "maxFontSize",
+    parser: PositiveNumber,
+    styles: &[],
+    apply_style: apply_max_font_size,
+};
+
+
 const FONT_STYLE_VARIANTS: &[FontStyle] = &[
     FontStyle::Normal,
     FontStyle::Italic,
@@ -135,6 +177,34 @@ pub static TEXT_WEIGHT: BuiltInStyle = BuiltInStyle {
 };
 
 
+fn apply_font_features(value: &StyleValue, context: &mut StyleContext) {
+    if let StyleValue::FontFeatures(features) = value {
+        context.set_font_features(*features);
+    }
+}
+
+pub static FONT_FEATURES: BuiltInStyle = BuiltInStyle {
+    name: "fontFeatures",
+    parser: FontFeatures,
+    styles: &[ ("fontFeatureSettings", Forward) ],
+    apply_style: apply_font_features,
+};
+
+
+fn apply_font_variations(value: &StyleValue, context: &mut StyleContext) {
+    if let StyleValue::FontVariations(variations) = value {
+        context.set_font_variations(*variations);
+    }
+}
+
+pub static FONT_VARIATIONS: BuiltInStyle = BuiltInStyle {
+    name: "fontVariations",
+    parser: FontVariations,
+    styles: &[ ("fontVariationSettings", Forward) ],
+    apply_style: apply_font_variations,
+};
+
+
 fn apply_text_letter_spacing(value: &StyleValue, context: &mut StyleContext) {
     if let Some(d) = dimension_to_context(value) {
         context.set_letter_spacing(d);
@@ -209,16 +279,96 @@ pub static TEXT_TRANSFORM: BuiltInStyle = BuiltInStyle {
 };
 
 
+fn apply_initial_letter(value: &StyleValue, context: &mut StyleContext) {
+    match value {
+        StyleValue::Match(..) => context.set_initial_letter(0),
+        StyleValue::Float(f) if *f >= 1.0 => context.set_initial_letter(f.round().min(u16::MAX as f32) as u16),
+        _ => {}
+    }
+}
+
+pub static INITIAL_LETTER_MATCHES: &[&str] = &[
+    "none",
+];
+
+/// Number of lines the enlarged first letter of a block spans
+pub static INITIAL_LETTER: BuiltInStyle = BuiltInStyle {
+    name: "initialLetter",
+    parser: MatchOrFloat(INITIAL_LETTER_MATCHES),
+    styles: &[],
+    apply_style: apply_initial_letter,
+};
+
+
+const VERTICAL_ALIGN_VARIANTS: &[VerticalAlign] = &[
+    VerticalAlign::Baseline,
+    VerticalAlign::Super,
+    VerticalAlign::Sub,
+];
+
+fn apply_vertical_align(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, VERTICAL_ALIGN_VARIANTS) {
+        context.set_vertical_align(v);
+    }
+}
+
+pub static VERTICAL_ALIGN_MATCHES: &[&str] = &[
+    "baseline",
+    "super",
+    "sub",
+];
+
+pub static VERTICAL_ALIGN: BuiltInStyle = BuiltInStyle {
+    name: "verticalAlign",
+    parser: Match(VERTICAL_ALIGN_MATCHES),
+    styles: &[
+        ("super", match_value(1, VERTICAL_ALIGN_MATCHES)),
+        ("sub", match_value(2, VERTICAL_ALIGN_MATCHES)),
+    ],
+    apply_style: apply_vertical_align,
+};
+
+
+const FONT_VARIANT_VARIANTS: &[FontVariant] = &[
+    FontVariant::Normal,
+    FontVariant::SmallCaps,
+];
+
+fn apply_font_variant(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, FONT_VARIANT_VARIANTS) {
+        context.set_font_variant(v);
+    }
+}
+
+pub static FONT_VARIANT_MATCHES: &[&str] = &[
+    "normal",
+    "smallCaps",
+];
+
+pub static FONT_VARIANT: BuiltInStyle = BuiltInStyle {
+    name: "fontVariant",
+    parser: Match(FONT_VARIANT_MATCHES),
+    styles: &[
+        ("smallCaps", match_value(1, FONT_VARIANT_MATCHES)),
+    ],
+    apply_style: apply_font_variant,
+};
+
 
 const TEXT_ALIGN_VARIANTS: &[TextAlign] = &[
     TextAlign::Left,
     TextAlign::Right,
     TextAlign::Center,
     TextAlign::Justify,
+    TextAlign::Start,
+    TextAlign::End,
+    TextAlign::Decimal('.'),
 ];
 
 fn apply_text_align(value: &StyleValue, context: &mut StyleContext) {
-    if let Some(v) = style_context_match(value, TEXT_ALIGN_VARIANTS) {
+    if let StyleValue::Decimal(c) = value {
+        context.set_text_align(TextAlign::Decimal(*c));
+    } else if let Some(v) = style_context_match(value, TEXT_ALIGN_VARIANTS) {
         context.set_text_align(v);
     }
 }
@@ -228,11 +378,14 @@ pub static TEXT_ALIGN_MATCHES: &[&str] = &[
     "right",
     "center",
     "justify",
+    "start",
+    "end",
+    "decimal",
 ];
 
 pub static TEXT_ALIGN: BuiltInStyle = BuiltInStyle {
     name: "textAlign",
-    parser: Match(TEXT_ALIGN_MATCHES),
+    parser: MatchOrDecimal(TEXT_ALIGN_MATCHES),
     styles: &[
         ("left", match_value(0, TEXT_ALIGN_MATCHES)),
         ("right", match_value(1, TEXT_ALIGN_MATCHES)),
@@ -243,6 +396,37 @@ pub static TEXT_ALIGN: BuiltInStyle = BuiltInStyle {
 };
 
 
+const DIRECTION_VARIANTS: &[Direction] = &[
+    Direction::Ltr,
+    Direction::Rtl,
+    Direction::Auto,
+];
+
+fn apply_direction(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, DIRECTION_VARIANTS) {
+        context.set_direction(v);
+    }
+}
+
+pub static DIRECTION_MATCHES: &[&str] = &[
+    "ltr",
+    "rtl",
+    "auto",
+];
+
+/// Auto takes the direction of the first strong character of a paragraph
+pub static DIRECTION: BuiltInStyle = BuiltInStyle {
+    name: "direction",
+    parser: Match(DIRECTION_MATCHES),
+    styles: &[
+        ("dir", Forward),
+        ("ltr", match_value(0, DIRECTION_MATCHES)),
+        ("rtl", match_value(1, DIRECTION_MATCHES)),
+    ],
+    apply_style: apply_direction,
+};
+
+
 
 const TEXT_DECORATION_VARIANTS: &[TextDecoration] = &[
     TextDecoration::None,
@@ -274,3 +458,83 @@ pub static TEXT_DECORATION: BuiltInStyle = BuiltInStyle {
     ],
     apply_style: apply_text_decoration,
 };
+
+
+
+fn apply_text_decoration_color(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(color) = style_context_color(value) {
+        context.set_text_decoration_color(color);
+    }
+}
+
+pub static TEXT_DECORATION_COLOR: BuiltInStyle = BuiltInStyle {
+    name: "textDecorationColor",
+    parser: Color,
+    styles: &[ ("decorationColor", Forward) ],
+    apply_style: apply_text_decoration_color,
+};
+
+
+fn apply_text_decoration_thickness(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(d) = dimension_to_context(value) {
+        context.set_text_decoration_thickness(d);
+    }
+}
+
+pub static TEXT_DECORATION_THICKNESS: BuiltInStyle = BuiltInStyle {
+    name: "textDecorationThickness",
+    parser: PositiveNumber,
+    styles: &[ ("decorationThickness", Forward) ],
+    apply_style: apply_text_decoration_thickness,
+};
+
+
+const TEXT_DECORATION_STYLE_VARIANTS: &[TextDecorationStyle] = &[
+    TextDecorationStyle::Solid,
+    TextDecorationStyle::Dashed,
+    TextDecorationStyle::Wavy,
+    TextDecorationStyle::Double,
+];
+
+fn apply_text_decoration_style(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, TEXT_DECORATION_STYLE_VARIANTS) {
+        context.set_text_decoration_style(v);
+    }
+}
+
+pub static TEXT_DECORATION_STYLE_MATCHES: &[&str] = &[
+    "solid",
+    "dashed",
+    "wavy",
+    "double",
+];
+
+pub static TEXT_DECORATION_STYLE: BuiltInStyle = BuiltInStyle {
+    name: "textDecorationStyle",
+    parser: Match(TEXT_DECORATION_STYLE_MATCHES),
+    styles: &[ ("decorationStyle", Forward) ],
+    apply_style: apply_text_decoration_style,
+};
+
+
+
+fn apply_lang(value: &StyleValue, context: &mut StyleContext) {
+    if let StyleValue::Match(_, code) = value && let [a, b] = code.as_bytes() {
+        context.set_lang([*a, *b]);
+    }
+}
+
+/// ISO 639-1 codes of the languages with embedded hyphenation patterns
+pub static LANG_MATCHES: &[&str] = &[
+    "af", "sq", "be", "bg", "ca", "hr", "cs", "da", "nl", "en", "et", "fi",
+    "fr", "ka", "de", "el", "hu", "is", "it", "ku", "la", "lt", "mn", "no",
+    "nb", "nn", "pl", "pt", "ru", "sr", "sk", "sl", "es", "sv", "tr", "tk",
+    "uk",
+];
+
+pub static LANG: BuiltInStyle = BuiltInStyle {
+    name: "lang",
+    parser: Match(LANG_MATCHES),
+    styles: &[ ("language", Forward) ],
+    apply_style: apply_lang,
+};
@@ -1,7 +1,7 @@
-use crate::styles::context::{StyleContext, WhiteSpace};
-use crate::styles::builtin::{match_value, style_context_match, BuiltInStyle};
+use crate::styles::context::{Hyphens, Leader, StyleContext, TextOverflow, WhiteSpace};
+use crate::styles::builtin::{float_to_context, match_value, style_context_match, BuiltInStyle};
 use crate::styles::style::{StyleValue};
-use crate::styles::style::StyleValueParser::Match;
+use crate::styles::style::StyleValueParser::{Float, Match, MatchOrFloat, TabStops};
 
 const WHITE_SPACE_VARIANTS: &[WhiteSpace] = &[
     WhiteSpace::Normal,
@@ -36,3 +36,143 @@ pub static WHITE_SPACE: BuiltInStyle = BuiltInStyle {
     apply_style: apply_white_space,
 };
 
+
+fn apply_tab_size(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(f) = float_to_context(value) && f >= 0.0 {
+        context.set_tab_size(f);
+    }
+}
+
+/// Number of spaces a tab advances to in preserved whitespace
+pub static TAB_SIZE: BuiltInStyle = BuiltInStyle {
+    name: "tabSize",
+    parser: Float,
+    styles: &[],
+    apply_style: apply_tab_size,
+};
+
+
+fn apply_tab_stops(value: &StyleValue, context: &mut StyleContext) {
+    if let StyleValue::TabStops(stops) = value {
+        context.set_tab_stops(*stops);
+    }
+}
+
+/// Positions tabs advance to, each aligning the text after the tab.
+/// Tabs past the last stop use tabSize
+pub static TAB_STOPS: BuiltInStyle = BuiltInStyle {
+    name: "tabStops",
+    parser: TabStops,
+    styles: &[],
+    apply_style: apply_tab_stops,
+};
+
+
+
+const LEADER_VARIANTS: &[Leader] = &[
+    Leader::None,
+    Leader::Dots,
+    Leader::Dashes,
+    Leader::Line,
+];
+
+fn apply_leader(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, LEADER_VARIANTS) {
+        context.set_leader(v);
+    }
+}
+
+pub static LEADER_MATCHES: &[&str] = &[
+    "none",
+    "dots",
+    "dashes",
+    "line",
+];
+
+/// Fills the gap a tab leaves before its stop
+pub static LEADER: BuiltInStyle = BuiltInStyle {
+    name: "leader",
+    parser: Match(LEADER_MATCHES),
+    styles: &[],
+    apply_style: apply_leader,
+};
+
+
+
+const HYPHENS_VARIANTS: &[Hyphens] = &[
+    Hyphens::None,
+    Hyphens::Manual,
+    Hyphens::Auto,
+];
+
+fn apply_hyphens(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, HYPHENS_VARIANTS) {
+        context.set_hyphens(v);
+    }
+}
+
+pub static HYPHENS_MATCHES: &[&str] = &[
+    "none",
+    "manual",
+    "auto",
+];
+
+/// Manual only breaks at soft hyphens, auto also uses the patterns for `lang`
+pub static HYPHENS: BuiltInStyle = BuiltInStyle {
+    name: "hyphens",
+    parser: Match(HYPHENS_MATCHES),
+    styles: &[
+        ("hyphenate", match_value(2, HYPHENS_MATCHES)),
+    ],
+    apply_style: apply_hyphens,
+};
+
+
+
+fn apply_line_clamp(value: &StyleValue, context: &mut StyleContext) {
+    match value {
+        StyleValue::Match(..) => context.set_line_clamp(0),
+        StyleValue::Float(f) if *f >= 1.0 => context.set_line_clamp(f.round().min(u16::MAX as f32) as u16),
+        _ => {}
+    }
+}
+
+pub static LINE_CLAMP_MATCHES: &[&str] = &[
+    "none",
+];
+
+/// Maximum number of lines shown by inline content, the rest is hidden
+pub static LINE_CLAMP: BuiltInStyle = BuiltInStyle {
+    name: "lineClamp",
+    parser: MatchOrFloat(LINE_CLAMP_MATCHES),
+    styles: &[],
+    apply_style: apply_line_clamp,
+};
+
+
+
+const TEXT_OVERFLOW_VARIANTS: &[TextOverflow] = &[
+    TextOverflow::Visible,
+    TextOverflow::Clip,
+    TextOverflow::Ellipsis,
+];
+
+fn apply_text_overflow(value: &StyleValue, context: &mut StyleContext) {
+    if let Some(v) = style_context_match(value, TEXT_OVERFLOW_VARIANTS) {
+        context.set_text_overflow(v);
+    }
+}
+
+pub static TEXT_OVERFLOW_MATCHES: &[&str] = &[
+    "visible",
+    "clip",
+    "ellipsis",
+];
+
+/// How the last visible line ends when text is cut by lineClamp or nowrap
+pub static TEXT_OVERFLOW: BuiltInStyle = BuiltInStyle {
+    name: "textOverflow",
+    parser: Match(TEXT_OVERFLOW_MATCHES),
+    styles: &[],
+    apply_style: apply_text_overflow,
+};
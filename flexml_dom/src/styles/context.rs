@@ -6,6 +6,9 @@ const POINT_DPI: f32 = 1.0 / 72.0;
 const MM_PER_INCH: f32 = 25.4;
 const INCHES_PER_MM: f32 = 1.0 / MM_PER_INCH;
 
+/// Smallest page width or height in pixels. Minimums for no particular reason, but we don't want 0
+pub const MIN_PAGE_DIMENSION: f32 = 50.0;
+
 #[derive(Clone, Copy, Debug, PartialEq, Default)]
 pub enum Dimension {
     #[default]
@@ -74,6 +77,96 @@ pub enum Display {
     Table,
 }
 
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Float {
+    #[default]
+    None,
+    Left,
+    Right,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Clear {
+    #[default]
+    None,
+    Left,
+    Right,
+    Both,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakBetween {
+    #[default]
+    Auto,
+    Page,
+    Avoid,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakInside {
+    #[default]
+    Auto,
+    Avoid,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageSize {
+    #[default]
+    None,
+    A3,
+    A4,
+    A5,
+    A6,
+    B5,
+    Letter,
+    Legal,
+    Tabloid,
+    Executive,
+    Receipt58mm,
+    Receipt80mm,
+}
+
+impl PageSize {
+    /// Portrait width and height of the paper. Receipt rolls only have a width,
+    /// their height grows with the content
+    pub fn dimensions(&self) -> Option<(Dimension, Option<Dimension>)> {
+        let mm = |width: f32, height: f32| Some((Dimension::Mm(width), Some(Dimension::Mm(height))));
+        let inch = |width: f32, height: f32| Some((Dimension::Inch(width), Some(Dimension::Inch(height))));
+
+        match self {
+            PageSize::None => None,
+            PageSize::A3 => mm(297.0, 420.0),
+            PageSize::A4 => mm(210.0, 297.0),
+            PageSize::A5 => mm(148.0, 210.0),
+            PageSize::A6 => mm(105.0, 148.0),
+            PageSize::B5 => mm(176.0, 250.0),
+            PageSize::Letter => inch(8.5, 11.0),
+            PageSize::Legal => inch(8.5, 14.0),
+            PageSize::Tabloid => inch(11.0, 17.0),
+            PageSize::Executive => inch(7.25, 10.5),
+            PageSize::Receipt58mm => Some((Dimension::Mm(58.0), None)),
+            PageSize::Receipt80mm => Some((Dimension::Mm(80.0), None)),
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageOrientation {
+    #[default]
+    Portrait,
+    Landscape,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageRegion {
+    #[default]
+    None,
+    Header,
+    Footer,
+    Overlay,
+    Underlay,
+}
+
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum WhiteSpace {
     #[default]
@@ -84,6 +177,40 @@ pub enum WhiteSpace {
     PreLine,
 }
 
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hyphens {
+    None,
+    #[default]
+    Manual,
+    Auto,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TabAlign {
+    #[default]
+    Left,
+    Right,
+    Center,
+    Decimal,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Leader {
+    #[default]
+    None,
+    Dots,
+    Dashes,
+    Line,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextOverflow {
+    #[default]
+    Visible,
+    Clip,
+    Ellipsis,
+}
+
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AlignContent {
@@ -149,11 +276,23 @@ pub enum JustifyContent {
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TextAlign {
-    #[default]
     Left,
     Right,
     Center,
     Justify,
+    #[default]
+    Start,
+    End,
+    /// Lines up sibling cells on the given character
+    Decimal(char),
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+    Auto,
 }
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
@@ -165,6 +304,30 @@ pub enum TextDecoration {
     LineThrough,
 }
 
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextDecorationStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Wavy,
+    Double,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerticalAlign {
+    #[default]
+    Baseline,
+    Super,
+    Sub,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FontVariant {
+    #[default]
+    Normal,
+    SmallCaps,
+}
+
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TextTransform {
     #[default]
@@ -224,6 +387,92 @@ pub enum BorderStyle {
 pub struct Color(pub u8, pub u8, pub u8, pub u8); // RGBA
 
 
+pub const MAX_FONT_SETTINGS: usize = 8;
+
+/// OpenType feature or variation axis settings keyed by tag.
+/// The list has a fixed capacity so style contexts stay Copy
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FontSettings<T: Copy> {
+    settings: [([u8; 4], T); MAX_FONT_SETTINGS],
+    len: usize,
+}
+
+pub type FontFeatures = FontSettings<u16>;
+pub type FontVariations = FontSettings<f32>;
+
+impl<T: Copy + Default> Default for FontSettings<T> {
+    fn default() -> Self {
+        Self {
+            settings: [([0; 4], T::default()); MAX_FONT_SETTINGS],
+            len: 0,
+        }
+    }
+}
+
+impl<T: Copy> FontSettings<T> {
+    /// Set a tag, replacing an earlier setting of the same tag.
+    /// Returns false when the list is full
+    pub fn set(&mut self, tag: [u8; 4], value: T) -> bool {
+        if let Some(setting) = self.settings[..self.len].iter_mut().find(|(t, _)| *t == tag) {
+            setting.1 = value;
+            return true;
+        }
+
+        if self.len == MAX_FONT_SETTINGS { return false }
+
+        self.settings[self.len] = (tag, value);
+        self.len += 1;
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &([u8; 4], T)> {
+        self.settings[..self.len].iter()
+    }
+}
+
+pub const MAX_TAB_STOPS: usize = 8;
+
+/// Tab stop positions with the alignment of the text that follows the tab.
+/// Fixed capacity like font settings so style contexts stay Copy
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TabStops {
+    stops: [(Dimension, TabAlign); MAX_TAB_STOPS],
+    len: usize,
+}
+
+impl Default for TabStops {
+    fn default() -> Self {
+        Self {
+            stops: [(Dimension::Zero, TabAlign::Left); MAX_TAB_STOPS],
+            len: 0,
+        }
+    }
+}
+
+impl TabStops {
+    /// Add a stop, returns false when the list is full
+    pub fn push(&mut self, position: Dimension, align: TabAlign) -> bool {
+        if self.len == MAX_TAB_STOPS { return false }
+
+        self.stops[self.len] = (position, align);
+        self.len += 1;
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(Dimension, TabAlign)> {
+        self.stops[..self.len].iter()
+    }
+}
+
+
 impl Color {
     fn transparent() -> Self {
         Self(0, 0, 0, 0)
@@ -234,7 +483,7 @@ impl Color {
 
 bitflags! {
     #[derive(Default, Clone, Copy, Debug, PartialEq)]
-    pub struct StyleBits: u64 {
+    pub struct StyleBits: u128 {
 
         const IS_ROOT              = 1 << 0;
 
@@ -299,6 +548,59 @@ bitflags! {
         const BORDER_COLOR         = 1 << 53;
         const BORDER_STYLE         = 1 << 54;
         const BORDER_WIDTH         = 1 << 55;
+
+        const TEXT_DECORATION_COLOR     = 1 << 56;
+        const TEXT_DECORATION_THICKNESS = 1 << 57;
+        const TEXT_DECORATION_STYLE     = 1 << 58;
+
+        const TAB_SIZE             = 1 << 59;
+        const HYPHENS              = 1 << 60;
+        const LANG                 = 1 << 61;
+        const DIRECTION            = 1 << 62;
+
+        const FONT_FEATURES        = 1 << 63;
+        const FONT_VARIATIONS      = 1 << 64;
+
+        const LINE_CLAMP           = 1 << 65;
+        const TEXT_OVERFLOW        = 1 << 66;
+
+        const FONT_SIZE_FIT        = 1 << 67;
+        const MIN_FONT_SIZE        = 1 << 68;
+        const MAX_FONT_SIZE        = 1 << 69;
+
+        const TAB_STOPS            = 1 << 70;
+        const LEADER               = 1 << 71;
+
+        const VERTICAL_ALIGN       = 1 << 72;
+        const FONT_VARIANT         = 1 << 73;
+
+        const SMART_PUNCTUATION    = 1 << 74;
+        const INITIAL_LETTER       = 1 << 75;
+
+        const COLUMN_COUNT         = 1 << 76;
+        const COLUMN_WIDTH         = 1 << 77;
+        const COLUMN_RULE_WIDTH    = 1 << 78;
+        const COLUMN_RULE_COLOR    = 1 << 79;
+
+        const FLOAT                = 1 << 80;
+        const CLEAR                = 1 << 81;
+
+        const BREAK_BEFORE         = 1 << 82;
+        const BREAK_AFTER          = 1 << 83;
+        const BREAK_INSIDE         = 1 << 84;
+        const WIDOWS               = 1 << 85;
+        const ORPHANS              = 1 << 86;
+
+        const PAGE_REGION          = 1 << 87;
+        const PAGE_SIZE            = 1 << 88;
+        const PAGE_ORIENTATION     = 1 << 89;
+        const PAGE_SECTION         = 1 << 90;
+        const PAGE_HEADER          = 1 << 91;
+        const PAGE_FOOTER          = 1 << 92;
+        const ROTATE               = 1 << 93;
+        const PAGE_BLEED           = 1 << 94;
+        const CROP_MARKS           = 1 << 95;
+        const PAGE_MAX_HEIGHT      = 1 << 96;
     }
 }
 
@@ -308,13 +610,27 @@ static INHERITABLE_STYLES: &[StyleBits] = &[
     StyleBits::FONT_SIZE,
     StyleBits::FONT_STYLE,
     StyleBits::FONT_WEIGHT,
+    StyleBits::FONT_FEATURES,
+    StyleBits::FONT_VARIATIONS,
     StyleBits::LETTER_SPACING,
     StyleBits::LINE_HEIGHT,
     StyleBits::TEXT_ALIGN,
+    StyleBits::DIRECTION,
     StyleBits::TEXT_DECORATION,
+    StyleBits::TEXT_DECORATION_COLOR,
+    StyleBits::TEXT_DECORATION_THICKNESS,
+    StyleBits::TEXT_DECORATION_STYLE,
     StyleBits::TEXT_TRANSFORM,
+    StyleBits::FONT_VARIANT,
     StyleBits::WHITE_SPACE,
+    StyleBits::TAB_SIZE,
+    StyleBits::TAB_STOPS,
+    StyleBits::LEADER,
+    StyleBits::HYPHENS,
+    StyleBits::LANG,
     StyleBits::WORD_SPACING,
+    StyleBits::WIDOWS,
+    StyleBits::ORPHANS,
 ];
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -329,8 +645,39 @@ pub struct StyleContext {
     dpi: f32,
 
     display: Display,
+    float: Float,
+    clear: Clear,
+    break_before: BreakBetween,
+    break_after: BreakBetween,
+    break_inside: BreakInside,
+    widows: u16,
+    orphans: u16,
+    page_region: PageRegion,
+    page_size: PageSize,
+    page_orientation: PageOrientation,
+    page_section: bool,
+    page_header: bool,
+    page_footer: bool,
+    page_bleed: Dimension,
+    crop_marks: bool,
+    page_max_height: Dimension,
     white_space: WhiteSpace,
+    tab_size: f32,
+    tab_stops: TabStops,
+    leader: Leader,
+    hyphens: Hyphens,
+    lang: [u8; 2],
+    line_clamp: u16,
+    text_overflow: TextOverflow,
+    smart_punctuation: bool,
+    initial_letter: u16,
     opacity: f32,
+    rotate: f32,
+
+    column_count: u16,
+    column_width: Dimension,
+    column_rule_width: Dimension,
+    column_rule_color: Color,
 
     margin_top: Dimension,
     margin_bottom: Dimension,
@@ -364,17 +711,28 @@ pub struct StyleContext {
     min_height: Dimension,
 
     text_align: TextAlign,
+    direction: Direction,
     color: Color,
     text_decoration: TextDecoration,
+    text_decoration_color: Color,
+    text_decoration_thickness: Dimension,
+    text_decoration_style: TextDecorationStyle,
     font_family: FontFamily,
     font_size: Dimension,
+    font_size_fit: bool,
+    min_font_size: Dimension,
+    max_font_size: Dimension,
     resolved_font_size: f32,
     resolved_root_font_size: f32,
     font_style: FontStyle,
     text_transform: TextTransform,
+    vertical_align: VerticalAlign,
+    font_variant: FontVariant,
     letter_spacing: Dimension,
     line_height: Dimension,
     font_weight: u16,
+    font_features: FontFeatures,
+    font_variations: FontVariations,
     word_spacing: Dimension,
 
     bg_color: Color,
@@ -410,13 +768,27 @@ impl StyleContext {
                     StyleBits::FONT_SIZE => self.set_font_size(parent.font_size),
                     StyleBits::FONT_STYLE => self.set_font_style(parent.font_style),
                     StyleBits::FONT_WEIGHT => self.set_font_weight(parent.font_weight),
+                    StyleBits::FONT_FEATURES => self.set_font_features(parent.font_features),
+                    StyleBits::FONT_VARIATIONS => self.set_font_variations(parent.font_variations),
                     StyleBits::LETTER_SPACING => self.set_letter_spacing(parent.letter_spacing),
                     StyleBits::LINE_HEIGHT => self.set_line_height(parent.line_height),
                     StyleBits::TEXT_ALIGN => self.set_text_align(parent.text_align),
+                    StyleBits::DIRECTION => self.set_direction(parent.direction),
                     StyleBits::TEXT_DECORATION => self.set_text_decoration(parent.text_decoration),
+                    StyleBits::TEXT_DECORATION_COLOR => self.set_text_decoration_color(parent.text_decoration_color),
+                    StyleBits::TEXT_DECORATION_THICKNESS => self.set_text_decoration_thickness(parent.text_decoration_thickness),
+                    StyleBits::TEXT_DECORATION_STYLE => self.set_text_decoration_style(parent.text_decoration_style),
                     StyleBits::TEXT_TRANSFORM => self.set_text_transform(parent.text_transform),
+                    StyleBits::FONT_VARIANT => self.set_font_variant(parent.font_variant),
                     StyleBits::WHITE_SPACE => self.set_white_space(parent.white_space),
+                    StyleBits::TAB_SIZE => self.set_tab_size(parent.tab_size),
+                    StyleBits::TAB_STOPS => self.set_tab_stops(parent.tab_stops),
+                    StyleBits::LEADER => self.set_leader(parent.leader),
+                    StyleBits::HYPHENS => self.set_hyphens(parent.hyphens),
+                    StyleBits::LANG => self.set_lang(parent.lang),
                     StyleBits::WORD_SPACING => self.set_word_spacing(parent.word_spacing),
+                    StyleBits::WIDOWS => self.set_widows(parent.widows),
+                    StyleBits::ORPHANS => self.set_orphans(parent.orphans),
                     _ => {}
                 }
 
@@ -445,9 +817,10 @@ impl StyleContext {
             parent.dpi,
         );
 
-        // Propagate root font size and dpi (unchanged from parent)
+        // Propagate root font size, dpi and document options (unchanged from parent)
         self.dpi = parent.dpi;
         self.resolved_root_font_size = parent.resolved_root_font_size;
+        self.smart_punctuation = parent.smart_punctuation;
     }
 
     pub fn prepare_root(&mut self) {
@@ -460,7 +833,6 @@ impl StyleContext {
 
         // Minimums for no particular reason, but we don't want 0
         let min_dpi: f32 = 100.0;
-        let min_page_dimension_resolved: f32 = 50.0;
         let min_font_size_resolved: f32 = 6.0;
 
         // Default page color
@@ -474,10 +846,31 @@ impl StyleContext {
         if !self.has_padding_bottom() { self.set_padding_bottom(Dimension::Inch(default_page_margin_inches)) }
         if !self.has_padding_right() { self.set_padding_right(Dimension::Inch(default_page_margin_inches)) }
 
+        // Named page sizes fill in the dimensions not set by user
+        if let Some((width, height)) = self.page_size().dimensions() {
+            let landscape = self.page_orientation() == PageOrientation::Landscape && height.is_some();
+            let (width, height) = match height {
+                Some(height) if landscape => (height, Some(width)),
+                _ => (width, height),
+            };
+
+            if !self.has_width() { self.set_width(width) }
+            if !self.has_height() { self.set_height(height.unwrap_or(Dimension::Auto)) }
+        }
+
         // Set default page dimensions if not set by user
         if !self.has_width() { self.set_width(Dimension::Inch(default_page_width_inches)) }
         if !self.has_height() { self.set_height(Dimension::Inch(default_page_height_inches)) }
 
+        // Without a named size the orientation turns the page dimensions
+        if !self.has_page_size() && self.page_orientation() == PageOrientation::Landscape {
+            let (width, height) = (self.width(), self.height());
+            if width.as_pixels(0.0, 0.0, 0.0, 1.0) < height.as_pixels(0.0, 0.0, 0.0, 1.0) {
+                self.set_width(height);
+                self.set_height(width);
+            }
+        }
+
         // Default font size if not user set
         if !self.has_font_size() { self.set_font_size(Dimension::Px(default_font_size_pixels)) }
 
@@ -496,8 +889,12 @@ impl StyleContext {
         // 50% for example would resolve to 50% of the default page width
         let default_page_width_resolved = default_page_width_inches * self.dpi();
         let default_page_height_resolved = default_page_height_inches * self.dpi();
-        self.set_width(Dimension::Resolved(min_page_dimension_resolved.max(self.width().as_pixels(default_page_width_resolved, self.resolved_root_font_size(), self.resolved_font_size(), self.dpi())).round()));
-        self.set_height(Dimension::Resolved(min_page_dimension_resolved.max(self.height().as_pixels(default_page_height_resolved, self.resolved_root_font_size(), self.resolved_font_size(), self.dpi())).round()));
+        self.set_width(Dimension::Resolved(MIN_PAGE_DIMENSION.max(self.width().as_pixels(default_page_width_resolved, self.resolved_root_font_size(), self.resolved_font_size(), self.dpi())).round()));
+
+        // Auto height pages are as tall as their content
+        if !self.height().is_none() {
+            self.set_height(Dimension::Resolved(MIN_PAGE_DIMENSION.max(self.height().as_pixels(default_page_height_resolved, self.resolved_root_font_size(), self.resolved_font_size(), self.dpi())).round()));
+        }
     }
 }
 
@@ -553,8 +950,39 @@ impl StyleContext {
     style_field!(dpi: f32, StyleBits::DPI);
 
     style_field!(display: Display, StyleBits::DISPLAY);
+    style_field!(float: Float, StyleBits::FLOAT);
+    style_field!(clear: Clear, StyleBits::CLEAR);
+    style_field!(break_before: BreakBetween, StyleBits::BREAK_BEFORE);
+    style_field!(break_after: BreakBetween, StyleBits::BREAK_AFTER);
+    style_field!(break_inside: BreakInside, StyleBits::BREAK_INSIDE);
+    style_field!(widows: u16, StyleBits::WIDOWS);
+    style_field!(orphans: u16, StyleBits::ORPHANS);
+    style_field!(page_region: PageRegion, StyleBits::PAGE_REGION);
+    style_field!(page_size: PageSize, StyleBits::PAGE_SIZE);
+    style_field!(page_orientation: PageOrientation, StyleBits::PAGE_ORIENTATION);
+    style_field!(page_section: bool, StyleBits::PAGE_SECTION);
+    style_field!(page_header: bool, StyleBits::PAGE_HEADER);
+    style_field!(page_footer: bool, StyleBits::PAGE_FOOTER);
+    style_field!(page_bleed: Dimension, StyleBits::PAGE_BLEED);
+    style_field!(crop_marks: bool, StyleBits::CROP_MARKS);
+    style_field!(page_max_height: Dimension, StyleBits::PAGE_MAX_HEIGHT);
     style_field!(white_space: WhiteSpace, StyleBits::WHITE_SPACE);
+    style_field!(tab_size: f32, StyleBits::TAB_SIZE);
+    style_field!(tab_stops: TabStops, StyleBits::TAB_STOPS);
+    style_field!(leader: Leader, StyleBits::LEADER);
+    style_field!(hyphens: Hyphens, StyleBits::HYPHENS);
+    style_field!(lang: [u8; 2], StyleBits::LANG);
+    style_field!(line_clamp: u16, StyleBits::LINE_CLAMP);
+    style_field!(text_overflow: TextOverflow, StyleBits::TEXT_OVERFLOW);
+    style_field!(smart_punctuation: bool, StyleBits::SMART_PUNCTUATION);
+    style_field!(initial_letter: u16, StyleBits::INITIAL_LETTER);
     style_field!(opacity: f32, StyleBits::OPACITY);
+    style_field!(rotate: f32, StyleBits::ROTATE);
+
+    style_field!(column_count: u16, StyleBits::COLUMN_COUNT);
+    style_field!(column_width: Dimension, StyleBits::COLUMN_WIDTH);
+    style_field!(column_rule_width: Dimension, StyleBits::COLUMN_RULE_WIDTH);
+    style_field!(column_rule_color: Color, StyleBits::COLUMN_RULE_COLOR);
 
     style_field!(margin_top: Dimension, StyleBits::MARGIN_TOP);
     style_field!(margin_bottom: Dimension, StyleBits::MARGIN_BOTTOM);
@@ -588,15 +1016,26 @@ impl StyleContext {
     style_field!(min_height: Dimension, StyleBits::MIN_HEIGHT);
 
     style_field!(text_align: TextAlign, StyleBits::TEXT_ALIGN);
+    style_field!(direction: Direction, StyleBits::DIRECTION);
     style_field!(color: Color, StyleBits::COLOR);
     style_field!(text_decoration: TextDecoration, StyleBits::TEXT_DECORATION);
+    style_field!(text_decoration_color: Color, StyleBits::TEXT_DECORATION_COLOR);
+    style_field!(text_decoration_thickness: Dimension, StyleBits::TEXT_DECORATION_THICKNESS);
+    style_field!(text_decoration_style: TextDecorationStyle, StyleBits::TEXT_DECORATION_STYLE);
     style_field!(font_family: FontFamily, StyleBits::FONT_FAMILY);
     style_field!(font_size: Dimension, StyleBits::FONT_SIZE);
+    style_field!(font_size_fit: bool, StyleBits::FONT_SIZE_FIT);
+    style_field!(min_font_size: Dimension, StyleBits::MIN_FONT_SIZE);
+    style_field!(max_font_size: Dimension, StyleBits::MAX_FONT_SIZE);
     style_field!(font_style: FontStyle, StyleBits::FONT_STYLE);
     style_field!(text_transform: TextTransform, StyleBits::TEXT_TRANSFORM);
+    style_field!(vertical_align: VerticalAlign, StyleBits::VERTICAL_ALIGN);
+    style_field!(font_variant: FontVariant, StyleBits::FONT_VARIANT);
     style_field!(letter_spacing: Dimension, StyleBits::LETTER_SPACING);
     style_field!(line_height: Dimension, StyleBits::LINE_HEIGHT);
     style_field!(font_weight: u16, StyleBits::FONT_WEIGHT);
+    style_field!(font_features: FontFeatures, StyleBits::FONT_FEATURES);
+    style_field!(font_variations: FontVariations, StyleBits::FONT_VARIATIONS);
     style_field!(word_spacing: Dimension, StyleBits::WORD_SPACING);
 
     style_field!(bg_color: Color, StyleBits::BG_COLOR);
@@ -625,8 +1064,38 @@ impl Default for StyleContext {
 
             bits: Default::default(),
             display: Display::Block,
+            float: Float::None,
+            clear: Clear::None,
+            break_before: BreakBetween::Auto,
+            break_after: BreakBetween::Auto,
+            break_inside: BreakInside::Auto,
+            widows: 2,
+            orphans: 2,
+            page_region: PageRegion::None,
+            page_size: PageSize::None,
+            page_orientation: PageOrientation::Portrait,
+            page_section: false,
+            page_header: true,
+            page_footer: true,
+            page_bleed: Dimension::Zero,
+            crop_marks: false,
+            page_max_height: Dimension::Auto,
             white_space: WhiteSpace::Normal,
+            tab_size: 8.0,
+            tab_stops: Default::default(),
+            leader: Default::default(),
+            hyphens: Default::default(),
+            lang: *b"en",
+            line_clamp: 0,
+            text_overflow: Default::default(),
+            smart_punctuation: false,
+            initial_letter: 0,
             opacity: 1.0,
+            rotate: 0.0,
+            column_count: 0,
+            column_width: Dimension::Auto,
+            column_rule_width: Dimension::Zero,
+            column_rule_color: Color::transparent(),
             margin_top: Dimension::Zero,
             margin_bottom: Dimension::Zero,
             margin_left: Dimension::Zero,
@@ -654,16 +1123,27 @@ impl Default for StyleContext {
             height: Dimension::Auto,
             max_height: Dimension::Auto,
             min_height: Dimension::Auto,
-            text_align: TextAlign::Left,
+            text_align: TextAlign::Start,
+            direction: Default::default(),
             color: Color(0,0,0,255),
             text_decoration: Default::default(),
+            text_decoration_color: Color::transparent(), // Falls back to the text color
+            text_decoration_thickness: Dimension::Auto, // Falls back to the font metrics
+            text_decoration_style: Default::default(),
             font_family: FontFamily::SansSerif,
             font_size: Dimension::Zero, //This will cascade from root
+            font_size_fit: false,
+            min_font_size: Dimension::Auto,
+            max_font_size: Dimension::Auto,
             font_style: Default::default(),
             text_transform: Default::default(),
+            vertical_align: Default::default(),
+            font_variant: Default::default(),
             letter_spacing: Default::default(),
             line_height: Dimension::Em(1.2),
             font_weight: 400,
+            font_features: Default::default(),
+            font_variations: Default::default(),
             word_spacing: Default::default(),
             bg_color: Color::transparent(),
             bg_image: Image::None,
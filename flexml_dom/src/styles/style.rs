@@ -1,7 +1,7 @@
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 use crate::strings::{Chars, ValueErrors, ValueHelp};
-use crate::styles::context::Dimension;
+use crate::styles::context::{Dimension, FontFeatures, FontVariations, TabAlign, TabStops};
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct Rgba {
@@ -20,12 +20,17 @@ pub enum PathType {
 
 pub enum StyleValueParser {
     MatchOrFloat(&'static [&'static str]),
+    MatchOrPositiveNumber(&'static [&'static str]),
+    MatchOrDecimal(&'static [&'static str]),
     Float,
     Number,
     PositiveNumber,
     Match(&'static [&'static str]),
     Color,
     Path(PathType),
+    FontFeatures,
+    FontVariations,
+    TabStops,
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -41,6 +46,10 @@ pub enum StyleValue {
     DirectoryPath(PathBuf),
     Match(u8, &'static str),
     Color(Rgba),
+    FontFeatures(FontFeatures),
+    FontVariations(FontVariations),
+    TabStops(TabStops),
+    Decimal(char),
     Font(PathId),
     Image(PathId),
     Directory(PathId),
@@ -109,14 +118,113 @@ impl StyleValueParser {
         match self {
             StyleValueParser::Match(matches) => Self::parse_match(matches, s),
             StyleValueParser::MatchOrFloat(matches) => Self::parse_match_or_float(matches, s),
+            StyleValueParser::MatchOrPositiveNumber(matches) => Self::parse_match_or_positive_number(matches, s),
+            StyleValueParser::MatchOrDecimal(matches) => Self::parse_match_or_decimal(matches, s),
             StyleValueParser::Color => Self::parse_color(s),
             StyleValueParser::Number => Self::parse_number(s),
             StyleValueParser::PositiveNumber => Self::parse_positive_number(s),
             StyleValueParser::Path(kind) => Self::parse_path(kind, s),
             StyleValueParser::Float => Self::parse_float(s),
+            StyleValueParser::FontFeatures => Self::parse_font_features(s),
+            StyleValueParser::FontVariations => Self::parse_font_variations(s),
+            StyleValueParser::TabStops => Self::parse_tab_stops(s),
         }
     }
 
+    /// OpenType tags are four printable ascii characters
+    fn parse_tag(s: &str) -> Option<[u8; 4]> {
+        let tag: [u8; 4] = s.as_bytes().try_into().ok()?;
+        tag.iter().all(|c| c.is_ascii_graphic()).then_some(tag)
+    }
+
+    /// Features are separated by spaces or commas, each may be quoted.
+    /// `tnum` turns a feature on, `-liga` turns it off and `salt=2` picks an alternate
+    fn parse_font_features(s: &str) -> StyleValue {
+        if s.is_empty() { return StyleValue::Empty }
+
+        let mut features = FontFeatures::default();
+
+        for setting in s.split([' ', '\t', ',']).map(|setting| setting.trim_matches('"')).filter(|setting| !setting.is_empty()) {
+            let (name, value) = if let Some(name) = setting.strip_prefix('-') {
+                (name, Some(0))
+            } else if let Some((name, value)) = setting.split_once('=') {
+                (name, value.parse::<u16>().ok())
+            } else {
+                (setting.trim_start_matches('+'), Some(1))
+            };
+
+            match (Self::parse_tag(name), value) {
+                (Some(tag), Some(value)) => {
+                    if !features.set(tag, value) {
+                        return StyleValue::Invalid(ValueErrors::FONT_SETTINGS_FULL, ValueHelp::FONT_FEATURES);
+                    }
+                }
+                _ => return StyleValue::Invalid(ValueErrors::FONT_FEATURES, ValueHelp::FONT_FEATURES),
+            }
+        }
+
+        StyleValue::FontFeatures(features)
+    }
+
+    /// Variations are axis tag and value pairs, `wght 650 wdth 90`
+    fn parse_font_variations(s: &str) -> StyleValue {
+        if s.is_empty() { return StyleValue::Empty }
+
+        let mut variations = FontVariations::default();
+        let mut parts = s.split([' ', '\t', ',']).map(|part| part.trim_matches('"')).filter(|part| !part.is_empty());
+
+        while let Some(name) = parts.next() {
+            let value = parts.next().and_then(|value| value.parse::<f32>().ok());
+
+            match (Self::parse_tag(name), value) {
+                (Some(tag), Some(value)) => {
+                    if !variations.set(tag, value) {
+                        return StyleValue::Invalid(ValueErrors::FONT_SETTINGS_FULL, ValueHelp::FONT_VARIATIONS);
+                    }
+                }
+                _ => return StyleValue::Invalid(ValueErrors::FONT_VARIATIONS, ValueHelp::FONT_VARIATIONS),
+            }
+        }
+
+        StyleValue::FontVariations(variations)
+    }
+
+    /// Stops are positions, each optionally followed by an alignment.
+    /// `"1in, 3in right, 4.5in decimal"`
+    fn parse_tab_stops(s: &str) -> StyleValue {
+        if s.is_empty() { return StyleValue::Empty }
+
+        let mut stops = TabStops::default();
+        let mut positions = vec![];
+
+        for part in s.split([' ', ',']).filter(|part| !part.is_empty()) {
+            let align = match part.to_ascii_lowercase().as_str() {
+                "left" => Some(TabAlign::Left),
+                "right" => Some(TabAlign::Right),
+                "center" => Some(TabAlign::Center),
+                "decimal" => Some(TabAlign::Decimal),
+                _ => None,
+            };
+
+            match (align, Self::parse_positive_number(part)) {
+                (Some(align), _) => match positions.last_mut() {
+                    Some((_, stop_align)) => *stop_align = align,
+                    None => return StyleValue::Invalid(ValueErrors::TAB_STOPS, ValueHelp::TAB_STOPS),
+                },
+                (None, StyleValue::PositiveNumber(position)) => positions.push((position, TabAlign::Left)),
+                _ => return StyleValue::Invalid(ValueErrors::TAB_STOPS, ValueHelp::TAB_STOPS),
+            }
+        }
+
+        for (position, align) in positions {
+            if !stops.push(position, align) {
+                return StyleValue::Invalid(ValueErrors::TAB_STOPS_FULL, ValueHelp::TAB_STOPS);
+            }
+        }
+
+        StyleValue::TabStops(stops)
+    }
+
     fn parse_match_or_float(matches: &'static [&'static str], s: &str) -> StyleValue {
         let value = StyleValueParser::parse_match(matches, s);
 
@@ -126,6 +234,28 @@ impl StyleValueParser {
         }
     }
 
+    fn parse_match_or_positive_number(matches: &'static [&'static str], s: &str) -> StyleValue {
+        let value = StyleValueParser::parse_match(matches, s);
+
+        match value {
+            StyleValue::Match(_,_) => value,
+            _ => Self::parse_positive_number(s)
+        }
+    }
+
+    /// `decimal(,)` picks the character numbers line up on, anything else is a match
+    fn parse_match_or_decimal(matches: &'static [&'static str], s: &str) -> StyleValue {
+        let Some(inner) = s.strip_prefix(Chars::DECIMAL_OPEN).and_then(|s| s.strip_suffix(')')) else {
+            return Self::parse_match(matches, s)
+        };
+
+        let mut chars = inner.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if !c.is_whitespace() => StyleValue::Decimal(c),
+            _ => StyleValue::Invalid(ValueErrors::DECIMAL, ValueHelp::DECIMAL)
+        }
+    }
+
     fn parse_float(s: &str) -> StyleValue {
         if let Ok(float) = s.parse::<f32>() {
             StyleValue::Float(float)
@@ -395,6 +525,27 @@ impl Display for StyleValue {
             StyleValue::Float(fl) => {
                 write!(f, "{}", fl)
             }
+            StyleValue::FontFeatures(features) => {
+                let features: Vec<String> = features.iter()
+                    .map(|(tag, value)| format!("{}={}", String::from_utf8_lossy(tag), value))
+                    .collect();
+                write!(f, "{}", features.join(" "))
+            }
+            StyleValue::FontVariations(variations) => {
+                let variations: Vec<String> = variations.iter()
+                    .map(|(tag, value)| format!("{} {}", String::from_utf8_lossy(tag), value))
+                    .collect();
+                write!(f, "{}", variations.join(" "))
+            }
+            StyleValue::TabStops(stops) => {
+                let stops: Vec<String> = stops.iter()
+                    .map(|(position, align)| format!("{:?} {:?}", position, align))
+                    .collect();
+                write!(f, "{}", stops.join(", "))
+            }
+            StyleValue::Decimal(c) => {
+                write!(f, "decimal({})", c)
+            }
             StyleValue::Unset => {
                 write!(f, "Uns")
             }
@@ -1,6 +1,6 @@
 use super::style::{AtomicStyle, PathId, PathType, RawStyle, StyleId, StyleValue, StyleValueParser};
 use crate::strings::{Chars, ValueErrors, ValueHelp};
-use crate::styles::builtin::{BuiltInStyle, DEFAULT_BUILTINS, ROOT_STYLE_NAME};
+use crate::styles::builtin::{BuiltInStyle, DEFAULT_BUILTINS, PAGE_SELECTORS, ROOT_STYLE_NAME};
 use crate::styles::context::StyleContext;
 use crate::styles::style::StyleValue::{Directory, Font, Image};
 use std::collections::{HashMap, HashSet};
@@ -168,6 +168,12 @@ impl StyleRegistry {
                 StyleValueParser::MatchOrFloat(matches) => {
                     &format!("Float value or one of: {}", matches.join(", "))
                 },
+                StyleValueParser::MatchOrPositiveNumber(matches) => {
+                    &format!("Positive number or one of: {}", matches.join(", "))
+                },
+                StyleValueParser::MatchOrDecimal(matches) => {
+                    &format!("decimal(character) or one of: {}", matches.join(", "))
+                },
                 StyleValueParser::Path(kind) => {
                     match kind {
                         PathType::Image => "Image",
@@ -175,6 +181,15 @@ impl StyleRegistry {
                         PathType::Directory => "Folder",
                     }
                 },
+                StyleValueParser::FontFeatures => {
+                    "font features (tnum -liga salt=2)"
+                }
+                StyleValueParser::FontVariations => {
+                    "font variations (wght 650 wdth 90)"
+                }
+                StyleValueParser::TabStops => {
+                    "tab stops (1in, 3in right, 4in decimal)"
+                }
             };
 
             println!("{}: {}", builtin.name, description)
@@ -205,6 +220,57 @@ impl StyleRegistry {
         root.prepare_root();
     }
 
+    /// Definition name of a root style page selector like `flexml:first`.
+    /// None when the name is not the root style or the selector is unknown
+    pub fn page_selector_name(name: &str, selector: &str) -> Option<String> {
+        if name != ROOT_STYLE_NAME { return None }
+
+        PAGE_SELECTORS.iter()
+            .find(|s| s.eq_ignore_ascii_case(selector))
+            .map(|selector| format!("{ROOT_STYLE_NAME}:{selector}"))
+    }
+
+    /// Font features and variations take lists of values, directly or through
+    /// a forwarding style like `fontFeatureSettings`
+    pub fn takes_list_value(&self, name: &str) -> bool {
+        let Some(&id) = self.names_map.get(name.trim_start_matches(Chars::FORWARD)) else { return false };
+        let is_list = |id: StyleId| id < self.first_style
+            && matches!(self.builtins[id].parser, StyleValueParser::FontFeatures | StyleValueParser::FontVariations);
+
+        is_list(id) || self.forwarders.get(&id).is_some_and(|forwards| forwards.iter().any(|&id| is_list(id)))
+    }
+
+    /// Section boxes start new pages with their own page style
+    pub fn is_page_section(&self, styles: &[AtomicStyle]) -> bool {
+        let mut section = StyleContext::default();
+        for atomic in styles {
+            (self.builtins[atomic.id].apply_style)(&atomic.value, &mut section);
+        }
+        section.page_section()
+    }
+
+    /// Resolve the root style of a page. The root style definition comes first, then
+    /// the page selector definitions in order and the styles of the section box
+    pub fn resolve_page_style(&self, selectors: &[&str], section: &[AtomicStyle]) -> StyleContext {
+        let definition = |name: &str| self.names_map.get(name)
+            .and_then(|id| self.definitions.get(id))
+            .map_or(&[][..], Vec::as_slice);
+
+        let selector_styles = selectors.iter()
+            .filter_map(|selector| Self::page_selector_name(ROOT_STYLE_NAME, selector))
+            .flat_map(|name| definition(&name));
+
+        let mut root = StyleContext::default();
+        root.set_as_root();
+
+        for atomic in definition(ROOT_STYLE_NAME).iter().chain(selector_styles).chain(section) {
+            (self.builtins[atomic.id].apply_style)(&atomic.value, &mut root);
+        }
+
+        root.prepare_root();
+        root
+    }
+
     /// Resolve local atomic styles and cascade
     /// styles from the parent style
     pub fn resolve_style(&self, parent: &StyleContext, styles: &[AtomicStyle]) -> StyleContext {
@@ -234,6 +300,11 @@ impl StyleRegistry {
         id
     }
 
+    /// Interned paths by PathId
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
     /// Interns a string path and returns its PathId.
     /// If the path already exists, returns its existing PathId.
     /// These paths are normalized so that paths that end in the same
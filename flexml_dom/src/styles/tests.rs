@@ -1,7 +1,7 @@
 use crate::styles::builtin::text::{TEXT_COLOR, TEXT_SIZE};
-use crate::styles::context::Dimension;
+use crate::styles::context::{Dimension, FontFeatures, FontVariations, TabAlign, TabStops};
 use super::style::StyleValue::{NegativeNumber, Empty, Invalid, Color, PositiveNumber};
-use super::style::{AtomicStyle, RawStyle, StyleId, StyleValueParser, Rgba};
+use super::style::{AtomicStyle, RawStyle, StyleId, StyleValue, StyleValueParser, Rgba};
 use super::style_registry::{StyleRegistry};
 
 // Helper to get a sorted vec of atomic style entries for comparison
@@ -145,6 +145,78 @@ fn test_style_value_number_parser_invalid(){
     }
 }
 
+#[test]
+fn test_style_value_match_or_positive_number_parser(){
+    let parser = StyleValueParser::MatchOrPositiveNumber(&["fit"]);
+
+    assert_eq!(parser.parse("fit"), StyleValue::Match(0, "fit"));
+    assert_eq!(parser.parse("12px"), PositiveNumber(Dimension::Px(12.0)));
+
+    for input in ["-12px", "fits", "big"] {
+        let result = parser.parse(input);
+        match result {
+            Invalid(_,_) => {},
+            _ => panic!("Expected Invalid StyleValue got {:?} from input {}", result, input)
+        }
+    }
+}
+
+#[test]
+fn test_style_value_match_or_decimal_parser(){
+    let parser = StyleValueParser::MatchOrDecimal(&["left", "decimal"]);
+
+    assert_eq!(parser.parse("decimal"), StyleValue::Match(1, "decimal"));
+    assert_eq!(parser.parse("decimal(,)"), StyleValue::Decimal(','));
+    assert_eq!(parser.parse("decimal(·)"), StyleValue::Decimal('·'));
+
+    for input in ["decimal()", "decimal(,.)", "decimal( )", "decimal(,", "middle"] {
+        let result = parser.parse(input);
+        match result {
+            Invalid(_,_) => {},
+            _ => panic!("Expected Invalid StyleValue got {:?} from input {}", result, input)
+        }
+    }
+}
+
+#[test]
+fn test_style_value_font_features_parser(){
+    let features_parser = StyleValueParser::FontFeatures;
+
+    let mut expected = FontFeatures::default();
+    expected.set(*b"tnum", 1);
+    expected.set(*b"ss01", 1);
+    expected.set(*b"liga", 0);
+    expected.set(*b"salt", 2);
+
+    assert_eq!(features_parser.parse("tnum ss01 -liga salt=2"), StyleValue::FontFeatures(expected));
+    assert_eq!(features_parser.parse("tnum,+ss01, -liga salt=2"), StyleValue::FontFeatures(expected));
+    assert_eq!(features_parser.parse("\"tnum\" \"ss01\" -liga \"salt=2\""), StyleValue::FontFeatures(expected));
+    assert_eq!(features_parser.parse(""), Empty);
+
+    for invalid in ["tabular", "-li", "salt=x", "salt=-1"] {
+        assert!(matches!(features_parser.parse(invalid), Invalid(..)), "{} should be invalid", invalid);
+    }
+
+    let too_many = "aaa1 aaa2 aaa3 aaa4 aaa5 aaa6 aaa7 aaa8 aaa9";
+    assert!(matches!(features_parser.parse(too_many), Invalid(..)));
+}
+
+#[test]
+fn test_style_value_font_variations_parser(){
+    let variations_parser = StyleValueParser::FontVariations;
+
+    let mut expected = FontVariations::default();
+    expected.set(*b"wght", 650.0);
+    expected.set(*b"wdth", 90.0);
+
+    assert_eq!(variations_parser.parse("wght 650 wdth 90"), StyleValue::FontVariations(expected));
+    assert_eq!(variations_parser.parse("wght 300, wght 650, wdth 90"), StyleValue::FontVariations(expected));
+
+    for invalid in ["wght", "wght bold", "weight 650", "650 wght"] {
+        assert!(matches!(variations_parser.parse(invalid), Invalid(..)), "{} should be invalid", invalid);
+    }
+}
+
 #[test]
 fn test_intern_and_resolve() {
     let mut registry = StyleRegistry::with_builtins();
@@ -260,4 +332,21 @@ fn test_register_style_nested_alias_expansion() {
 
     assert_eq!(fancy_box_definition[0], AtomicStyle { id: atomic_font_size, value: PositiveNumber(Dimension::Px(23f32)) });
     assert_eq!(fancy_box_definition[1], AtomicStyle { id: atomic_color, value: Color(Rgba{r: 0,g: 0,b: 255,a: 255}) });
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_style_value_tab_stops_parser(){
+    let tab_stops_parser = StyleValueParser::TabStops;
+
+    let mut expected = TabStops::default();
+    expected.push(Dimension::Inch(1.0), TabAlign::Left);
+    expected.push(Dimension::Inch(3.0), TabAlign::Right);
+    expected.push(Dimension::Percent(90.0), TabAlign::Decimal);
+
+    assert_eq!(tab_stops_parser.parse("1in, 3in right, 90% decimal"), StyleValue::TabStops(expected));
+    assert_eq!(tab_stops_parser.parse("1in 3in RIGHT 90% decimal"), StyleValue::TabStops(expected));
+
+    for invalid in ["right", "1in middle", "-1in", "1in 2in 3in 4in 5in 6in 7in 8in 9in"] {
+        assert!(matches!(tab_stops_parser.parse(invalid), Invalid(..)), "{} should be invalid", invalid);
+    }
+}
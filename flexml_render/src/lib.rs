@@ -1,15 +1,16 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Result};
-use vello::kurbo::{Affine, RoundedRect, RoundedRectRadii, Stroke};
-use vello::peniko::{Color, Fill};
+use vello::kurbo::{Affine, BezPath, Line, RoundedRect, RoundedRectRadii, Stroke};
+use vello::peniko::{Blob, Color, Fill, Image, ImageFormat, Mix};
 use vello::util::{block_on_wgpu, RenderContext};
 use vello::RendererOptions;
 use vello::{kurbo, Scene};
 
-use flexml_dom::layout::fragments::{FragmentGroup, Rect as FragmentRect};
+use flexml_dom::layout::fragments::{BgPosition, BgRepeat, BgSize, DecorationStyle, Fragment, FragmentGroup, Radius, Rect as FragmentRect};
 
 use flexml_dom::layout::fragments::FragmentKind;
 use flexml_dom::layout::{FlexmlLayout};
@@ -27,13 +28,175 @@ fn kurbo_rect_from_bounds(bounds: &FragmentRect) -> kurbo::Rect {
     )
 }
 
-fn render_fragment_group(scene: &mut Scene, group: &FragmentGroup) {
+/// Images of a layout by path id, decoded on first use.
+/// Files that are not PNG images are skipped
+struct Images<'a> {
+    paths: &'a [PathBuf],
+    decoded: HashMap<usize, Option<Image>>,
+}
+
+impl<'a> Images<'a> {
+    fn new(paths: &'a [PathBuf]) -> Self {
+        Self { paths, decoded: HashMap::new() }
+    }
+
+    fn get(&mut self, image: usize) -> Option<&Image> {
+        let paths = self.paths;
+        self.decoded
+            .entry(image)
+            .or_insert_with(|| paths.get(image).and_then(|path| decode_png(path)))
+            .as_ref()
+    }
+}
+
+fn decode_png(path: &Path) -> Option<Image> {
+    let mut decoder = png::Decoder::new(File::open(path).ok()?);
+    decoder.set_transformations(png::Transformations::normalize_to_color8() | png::Transformations::ALPHA);
+
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).ok()?;
+    buffer.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::GrayscaleAlpha => buffer.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        _ => return None,
+    };
+
+    Some(Image::new(Blob::from(rgba), ImageFormat::Rgba8, info.width, info.height))
+}
+
+fn rounded_rect(bounds: &FragmentRect, radius: &Radius) -> RoundedRect {
+    RoundedRect::from_rect(
+        kurbo_rect_from_bounds(bounds),
+        RoundedRectRadii::new(
+            radius.top_left as f64,
+            radius.top_right as f64,
+            radius.bottom_left as f64,
+            radius.bottom_right as f64,
+        ),
+    )
+}
+
+/// Offsets of the image tiles along one axis. Repeated images cover the
+/// whole box starting from the placed tile
+fn image_tiles(placed: f64, tile: f64, start: f64, end: f64, repeat: bool) -> Vec<f64> {
+    if !repeat || tile < 1.0 { return vec![placed] }
+
+    let mut offset = placed - ((placed - start) / tile).ceil() * tile;
+    let mut tiles = vec![];
+    while offset < end {
+        tiles.push(offset);
+        offset += tile;
+    }
+    tiles
+}
+
+/// Background images are sized and placed in the area of their box, then
+/// clipped to the bounds, which only hold the part of a box on this page
+fn render_image(scene: &mut Scene, transform: Affine, image: &Image, fragment: &Fragment) {
+    let FragmentKind::Image { area, radius, size, position, repeat, .. } = &fragment.kind else { return };
+    let bounds = &fragment.bounds;
+    let (box_x, box_y) = (area.x as f64, area.y as f64);
+    let (box_width, box_height) = (area.width as f64, area.height as f64);
+    let (clip_x, clip_y) = (bounds.x as f64, bounds.y as f64);
+    let (clip_right, clip_bottom) = (clip_x + bounds.width as f64, clip_y + bounds.height as f64);
+    let (image_width, image_height) = (image.width as f64, image.height as f64);
+
+    let scale = match size {
+        BgSize::Auto => 1.0,
+        BgSize::Contain => (box_width / image_width).min(box_height / image_height),
+        BgSize::Cover => (box_width / image_width).max(box_height / image_height),
+    };
+    let (width, height) = (image_width * scale, image_height * scale);
+
+    let center_x = box_x + (box_width - width) / 2.0;
+    let center_y = box_y + (box_height - height) / 2.0;
+    let (x, y) = match position {
+        BgPosition::Center => (center_x, center_y),
+        BgPosition::Top => (center_x, box_y),
+        BgPosition::Bottom => (center_x, box_y + box_height - height),
+        BgPosition::Left => (box_x, center_y),
+        BgPosition::Right => (box_x + box_width - width, center_y),
+    };
+
+    let repeat_x = matches!(repeat, BgRepeat::Repeat | BgRepeat::RepeatX);
+    let repeat_y = matches!(repeat, BgRepeat::Repeat | BgRepeat::RepeatY);
+
+    scene.push_layer(Mix::Clip, 1.0, transform, &rounded_rect(bounds, radius));
+    for tile_y in image_tiles(y, height, clip_y, clip_bottom, repeat_y) {
+        for &tile_x in &image_tiles(x, width, clip_x, clip_right, repeat_x) {
+            scene.draw_image(image, transform * Affine::translate((tile_x, tile_y)) * Affine::scale(scale));
+        }
+    }
+    scene.pop_layer();
+}
+
+fn render_text_decoration(scene: &mut Scene, transform: Affine, bounds: &FragmentRect, color: Color, thickness: f32, style: DecorationStyle) {
+    let thickness = thickness as f64;
+    let x0 = bounds.x as f64;
+    let x1 = (bounds.x + bounds.width) as f64;
+    let center_y = bounds.y as f64 + thickness / 2.0;
+
+    match style {
+        DecorationStyle::Solid => {
+            scene.fill(Fill::NonZero, transform, color, None, &kurbo_rect_from_bounds(bounds));
+        }
+        DecorationStyle::Double => {
+            let mut second = *bounds;
+            second.y += bounds.height * 2.0;
+
+            scene.fill(Fill::NonZero, transform, color, None, &kurbo_rect_from_bounds(bounds));
+            scene.fill(Fill::NonZero, transform, color, None, &kurbo_rect_from_bounds(&second));
+        }
+        DecorationStyle::Dashed => {
+            let stroke = Stroke::new(thickness).with_dashes(0.0, [thickness * 3.0, thickness * 2.0]);
+            scene.stroke(&stroke, transform, color, None, &Line::new((x0, center_y), (x1, center_y)));
+        }
+        DecorationStyle::Wavy => {
+            // Quadratic waves, one wavelength is four thicknesses wide
+            let half_wave = (thickness * 2.0).max(1.0);
+            let amplitude = thickness;
+            let mut path = BezPath::new();
+            let mut x = x0;
+            let mut up = true;
+
+            path.move_to((x, center_y));
+            while x < x1 {
+                let next_x = (x + half_wave).min(x1);
+                let control_y = if up { center_y - amplitude * 2.0 } else { center_y + amplitude * 2.0 };
+                path.quad_to(((x + next_x) / 2.0, control_y), (next_x, center_y));
+                x = next_x;
+                up = !up;
+            }
+
+            scene.stroke(&Stroke::new(thickness), transform, color, None, &path);
+        }
+    }
+}
+
+/// Groups are rotated around the center of their whole box, and painted in their own
+/// layer clipped to the page when partly transparent
+fn render_fragment_group(scene: &mut Scene, group: &FragmentGroup, transform: Affine, page: &kurbo::Rect, images: &mut Images) {
+    let transform = if group.rotation != 0.0 {
+        let center = kurbo_rect_from_bounds(&group.area).center();
+        transform * Affine::rotate_about((group.rotation as f64).to_radians(), center)
+    } else {
+        transform
+    };
+
+    let layered = group.opacity < 1.0;
+    if layered {
+        scene.push_layer(Mix::Normal, group.opacity.max(0.0), Affine::IDENTITY, page);
+    }
+
     for fragment in &group.fragments {
         match &fragment.kind {
             FragmentKind::ColorBackground { color, radius } => {
                 scene.fill(
                     Fill::NonZero,
-                    Affine::IDENTITY,
+                    transform,
                     Color::from_rgba8(color.0, color.1, color.2, color.3),
                     None,
                     &RoundedRect::from_rect(
@@ -56,7 +219,7 @@ fn render_fragment_group(scene: &mut Scene, group: &FragmentGroup) {
 
                     scene.stroke(
                         &stroke,
-                        Affine::IDENTITY,
+                        transform,
                         Color::from_rgba8(color.0, color.1, color.2, color.3),
                         None,
                         &RoundedRect::from_rect(
@@ -71,6 +234,21 @@ fn render_fragment_group(scene: &mut Scene, group: &FragmentGroup) {
                     );
                 }
             }
+            FragmentKind::TextDecoration { color, thickness, style } => {
+                render_text_decoration(
+                    scene,
+                    transform,
+                    &fragment.bounds,
+                    Color::from_rgba8(color.0, color.1, color.2, color.3),
+                    *thickness,
+                    *style,
+                );
+            }
+            FragmentKind::Image { image, .. } => {
+                if let Some(image) = images.get(*image) {
+                    render_image(scene, transform, image, fragment);
+                }
+            }
             FragmentKind::Debug => {
                 let stroke = Stroke {
                     width: 1.0,
@@ -79,7 +257,7 @@ fn render_fragment_group(scene: &mut Scene, group: &FragmentGroup) {
 
                 scene.stroke(
                     &stroke,
-                    Affine::IDENTITY,
+                    transform,
                     Color::from_rgba8(255,0,0, 50),
                     None,
                     &RoundedRect::from_rect(
@@ -119,7 +297,7 @@ fn render_fragment_group(scene: &mut Scene, group: &FragmentGroup) {
                     .draw_glyphs(&glyph_run.font)
                     .brush(color)
                     .hint(false)
-                    .transform(Affine::IDENTITY)
+                    .transform(transform)
                     .glyph_transform(glyph_xform)
                     .font_size(glyph_run.font_size)
                     .normalized_coords(glyph_run.normalized_coords.as_slice())
@@ -129,7 +307,11 @@ fn render_fragment_group(scene: &mut Scene, group: &FragmentGroup) {
     }
 
     for subgroup in &group.subgroups {
-        render_fragment_group(scene, subgroup);
+        render_fragment_group(scene, subgroup, transform, page, images);
+    }
+
+    if layered {
+        scene.pop_layer();
     }
 }
 
@@ -154,13 +336,16 @@ pub async fn render_layout(layout: &FlexmlLayout, output_path: &PathBuf) -> Resu
 
     let mut scene = Scene::new();
 
-    for group in &layout.pages[0].fragments {
+    let page = &layout.pages[0];
+    let page_rect = kurbo::Rect::new(0.0, 0.0, page.width as f64, page.height as f64);
+    let mut images = Images::new(&layout.paths);
+    for group in &page.fragments {
         group.print_tree("FRAGMENT GROUP");
-        render_fragment_group(&mut scene, group);
+        render_fragment_group(&mut scene, group, Affine::IDENTITY, &page_rect, &mut images);
     }
 
-    let width = layout.page_width as u32;
-    let height = layout.page_height as u32;
+    let width = page.width as u32;
+    let height = page.height as u32;
 
     println!("Page w {} h {}", width, height);
 